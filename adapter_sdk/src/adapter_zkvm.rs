@@ -131,8 +131,10 @@ pub fn verify_proof<P: RollupProof>(
                     state_root: i.state_root,
                     start_nexus_hash: i.start_nexus_hash,
                     app_id: app_account_id,
-                    img_id: i.img_id,
+                    img_id: img_id.clone(),
                     rollup_hash: i.rollup_hash,
+                    message_root: None,
+                    storage_hash: None,
                 },
                 None => AdapterPublicInputs {
                     nexus_hash: nexus_hash.clone(),
@@ -142,6 +144,8 @@ pub fn verify_proof<P: RollupProof>(
                     app_id: app_account_id,
                     img_id: img_id.clone(),
                     rollup_hash: Some(H256::zero()),
+                    message_root: None,
+                    storage_hash: None,
                 },
             });
         }
@@ -169,6 +173,8 @@ pub fn verify_proof<P: RollupProof>(
                         app_id: app_account_id,
                         img_id: img_id.clone(),
                         rollup_hash: Some(H256::zero()),
+                        message_root: rollup_public_inputs.message_root,
+                        storage_hash: None,
                     })
                 }
             }
@@ -185,8 +191,21 @@ pub fn verify_proof<P: RollupProof>(
         ));
     }
 
+    //Previous proof is generated by the program of the statement before a rotation,
+    //so it is verified against its own statement.
+    let prev_statement: StatementDigest = match private_inputs.prev_statement {
+        Some(i) => i,
+        None => img_id.clone(),
+    };
+
+    if prev_public_input.img_id != prev_statement {
+        return Err(anyhow::anyhow!(
+            "Previous proof not generated for the previous statement."
+        ));
+    }
+
     #[cfg(feature = "zkvm-risc0")]
-    match env::verify(img_id.0, &to_vec(&prev_public_input).unwrap()) {
+    match env::verify(prev_statement.0, &to_vec(&prev_public_input).unwrap()) {
         Ok(()) => {
             println!("Verified proof");
             ()
@@ -202,5 +221,7 @@ pub fn verify_proof<P: RollupProof>(
         app_id: app_account_id,
         img_id: img_id.clone(),
         rollup_hash: prev_public_input.rollup_hash,
+        message_root: rollup_public_inputs.message_root,
+        storage_hash: None,
     })
 }
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
 use anyhow::anyhow;
-use nexus_core::types::{AccountState, AccountWithProof, NexusHeader, Transaction, H256};
+use nexus_core::types::{
+    AccountState, AccountWithProof, HeaderWindow, NexusHeader, Transaction, H256,
+};
 
 #[derive(Debug, Clone)]
 pub struct NexusAPI {
@@ -76,6 +78,25 @@ impl NexusAPI {
         Ok(range)
     }
 
+    pub async fn get_header_window(&self) -> Result<HeaderWindow, anyhow::Error> {
+        let response = self
+            .client
+            .get(&format!("{}/header_window", self.url))
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let header_window: HeaderWindow = response.json().await?;
+
+            Ok(header_window)
+        } else {
+            Err(anyhow!(
+                "Request failed with status code: {}",
+                response.status()
+            ))
+        }
+    }
+
     pub async fn get_account_state(
         &self,
         app_account_id: &H256,
@@ -13,7 +13,7 @@ use anyhow::{anyhow, Error};
 use nexus_core::types::Proof;
 use nexus_core::types::{
     AppAccountId, AppId, AvailHeader, InitAccount, NexusHeader, Proof as ZKProof, StatementDigest,
-    SubmitProof, Transaction, TxParams, TxSignature, H256,
+    SubmitProof, Transaction, TxParams, TxSigningKey, H256,
 };
 #[cfg(feature = "native-risc0")]
 use nexus_core::zkvm::risczero::{ProofConversion, RiscZeroProver};
@@ -83,6 +83,7 @@ pub struct AdapterState<
     pub nexus_api: NexusAPI,
     pub prover_mode: ProverMode,
     pub avail_url: String,
+    pub signing_key: TxSigningKey,
 }
 
 impl<
@@ -118,6 +119,7 @@ where
             nexus_api: NexusAPI::new(&"http://127.0.0.1:7000"),
             prover_mode: config.prover_mode,
             avail_url: config.avail_url,
+            signing_key: config.signing_key,
         }
     }
 
@@ -149,14 +151,17 @@ where
         if self.previous_adapter_proof.is_none() {
             let header_hash = relayer.get_header_hash(self.starting_block_number).await;
             let nexus_hash: H256 = self.nexus_api.get_header(&header_hash).await?.hash();
-            let tx = Transaction {
-                signature: TxSignature([0u8; 64]),
-                params: TxParams::InitAccount(InitAccount {
+            let tx = Transaction::new_signed(
+                TxParams::InitAccount(InitAccount {
                     app_id: AppAccountId::from(self.app_id.clone()),
                     statement: self.elf_id.clone(),
+                    statement_kind: Z::proof_kind(),
                     start_nexus_hash: nexus_hash,
+                    owner: self.signing_key.public_key(),
                 }),
-            };
+                0,
+                &self.signing_key,
+            );
 
             let client = reqwest::Client::new();
 
@@ -206,9 +211,10 @@ where
             }
         });
         let nexus_api_clone = self.nexus_api.clone();
+        let signing_key = self.signing_key.clone();
         let submission_handle = tokio::spawn(async move {
             let nexus_api = nexus_api_clone;
-            Self::manage_submissions(db_clone_2, &nexus_api).await
+            Self::manage_submissions(db_clone_2, &nexus_api, &signing_key).await
         });
 
         match self.process_queue().await {
@@ -227,6 +233,7 @@ where
     async fn manage_submissions(
         db: Arc<Mutex<DB<P, ZP>>>,
         nexus_api: &NexusAPI,
+        signing_key: &TxSigningKey,
     ) -> Result<P, Error> {
         loop {
             thread::sleep(Duration::from_secs(2));
@@ -276,9 +283,15 @@ where
             if is_in_range {
                 println!("INside match");
                 let client = reqwest::Client::new();
-                let tx = Transaction {
-                    signature: TxSignature([0u8; 64]),
-                    params: TxParams::SubmitProof(SubmitProof {
+                let nonce = match nexus_api
+                    .get_account_state(&latest_proof.1.app_id.as_h256())
+                    .await
+                {
+                    Ok(i) => i.account.nonce,
+                    Err(e) => continue,
+                };
+                let tx = Transaction::new_signed(
+                    TxParams::SubmitProof(SubmitProof {
                         proof: match latest_proof.0.try_into() {
                             Ok(i) => i,
                             Err(e) => return Err(anyhow!(e)),
@@ -288,8 +301,12 @@ where
                         state_root: latest_proof.1.state_root,
                         app_id: latest_proof.1.app_id,
                         data: None,
+                        message_root: latest_proof.1.message_root,
+                        storage_hash: None,
                     }),
-                };
+                    nonce,
+                    signing_key,
+                );
 
                 let response = client
                     .post("http://127.0.0.1:7000/tx")
@@ -421,6 +438,10 @@ where
             nexus_header,
             avail_header: queue_item.header.clone(),
             app_id: self.app_id.clone(),
+            prev_statement: self
+                .previous_adapter_proof
+                .as_ref()
+                .map(|(_, pi, _)| pi.img_id.clone()),
         };
 
         let prev_pi_and_receipt = match &self.previous_adapter_proof {
@@ -1,7 +1,7 @@
 use crate::traits::RollupProof;
 pub use nexus_core::types::NexusRollupPI as AdapterPublicInputs;
 use nexus_core::{
-    types::{AppId, AvailHeader, NexusHeader, StatementDigest, H256},
+    types::{AppId, AvailHeader, NexusHeader, StatementDigest, TxSigningKey, H256},
     zkvm::ProverMode,
 };
 use serde::{Deserialize, Serialize};
@@ -11,6 +11,9 @@ pub struct AdapterPrivateInputs {
     pub nexus_header: NexusHeader,
     pub avail_header: AvailHeader,
     pub app_id: AppId,
+    //Statement the previous adapter proof was generated for. Differs from the
+    //current statement only for the first proof after a rotation.
+    pub prev_statement: Option<StatementDigest>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +22,8 @@ pub struct RollupPublicInputs {
     pub post_state_root: H256,
     pub height: u32,
     pub blob_hash: H256,
+    //Root of messages exported by the rollup at this height.
+    pub message_root: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,4 +41,5 @@ pub struct AdapterConfig {
     pub rollup_start_height: u32,
     pub prover_mode: ProverMode,
     pub avail_url: String,
+    pub signing_key: TxSigningKey,
 }
@@ -19,12 +19,24 @@ contract NexusProofManager {
     error InvalidBlockNumber(uint256 blockNumber, uint256 latestBlockNumber);
     error NexusLeafInclusionCheckFailed();
 
+    // Field order matches the ethabi encoding of AccountState in nexus core,
+    // which is the leaf value of the nexus state tree.
     struct AccountState {
         bytes32 statementDigest;
         bytes32 stateRoot;
         bytes32 startNexusHash;
         uint128 lastProofHeight;
         uint128 height;
+        bytes32 owner;
+        uint64 nonce;
+        bytes32 pendingStatement;
+        uint128 pendingStatementHeight;
+        bytes32 messageRoot;
+        bytes32 rollupHash;
+        bytes32 storageHash;
+        uint8 statementKind;
+        uint8 pendingStatementKind;
+        uint64 generation;
     }
 
     // nexus state root
@@ -50,18 +62,10 @@ contract NexusProofManager {
         bytes32 key,
         AccountState calldata accountState
     ) external {
-        bytes32 valueHash = sha256(
-            abi.encode(
-                accountState.statementDigest,
-                accountState.stateRoot,
-                accountState.startNexusHash,
-                accountState.lastProofHeight,
-                accountState.height
-            )
-        );
+        bytes32 valueHash = accountStateValueHash(accountState);
         JellyfishMerkleTreeVerifier.Leaf
             memory leaf = JellyfishMerkleTreeVerifier.Leaf({
-                addr: key,
+                addr: accountKey(key),
                 valueHash: valueHash
             });
 
@@ -80,6 +84,19 @@ contract NexusProofManager {
         nexusAppIDToState[key][accountState.height] = accountState.stateRoot;
     }
 
+    // Accounts are stored under a prefixed hash of the app account id in the
+    // nexus state tree, matching AppAccountId::account_key in nexus core.
+    function accountKey(bytes32 appAccountId) internal pure returns (bytes32) {
+        return sha256(abi.encodePacked("account", appAccountId));
+    }
+
+    // All fields are static, so the struct is encoded as its fields in order.
+    function accountStateValueHash(
+        AccountState memory accountState
+    ) internal pure returns (bytes32) {
+        return sha256(abi.encode(accountState));
+    }
+
     function verifyRollupState(
         bytes32 root,
         JellyfishMerkleTreeVerifier.Proof memory proof,
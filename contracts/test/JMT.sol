@@ -28,7 +28,7 @@ contract EthereumVerifierTest is Test {
     function testEmptyProof() public {
         uint256 blockNumber = 123;
 
-        bytes32 stateRoot = 0x118eabaae552430cdecf445736d2e57c5dbcf70c1688f053e70f0c3a6a80411f;
+        bytes32 stateRoot = 0x28709f6d0865d01f3392ee8aa605dc0d95cd1b90a582dd0bb9236007103bca1e;
         bytes32 blockHash = 0x118eabaae552430cdecf445736d2e57c5dbcf70c1688f053e70f0c3a6a80411f;
         bytes32 appid = 0x3655ca59b7d566ae06297c200f98d04da2e8e89812d627bc29297c25db60362d;
 
@@ -43,7 +43,17 @@ contract EthereumVerifierTest is Test {
                 0xd62c0e6039b3b76b0c70301de2dee44f1f8d1335e7df9bd26fc3bdb6f33a2574,
                 0x378f4888b185704cb8c8e86792838c2fed7f7d4bd58cd9e66b34050a9c42aad1,
                 570,
-                123
+                123,
+                bytes32(0),
+                0,
+                bytes32(0),
+                0,
+                bytes32(0),
+                bytes32(0),
+                bytes32(0),
+                0,
+                0,
+                0
             );
 
         proofManager.updateChainState(blockNumber, siblings, appid, state);
@@ -51,7 +61,7 @@ contract EthereumVerifierTest is Test {
 
     function testNonEmptyProof() public {
         uint256 blockNumber = 16;
-        bytes32 stateRoot = 0x01eabe125b5f4f9ce2b9c3cc3c306fe789bd6f6ef28aa8d2fb2254e1be045e38;
+        bytes32 stateRoot = 0x326466465fbe6524128d28e439ac2531ebe1fef7cd41a4d1c758ed30e265ccc8;
         bytes32 blockHash = 0x01eabe125b5f4f9ce2b9c3cc3c306fe789bd6f6ef28aa8d2fb2254e1be045e38;
         bytes32 appid = 0xa40fb80ad4287819ecda5efac01c74c78d7cb00ca5f9eb5f6c0f19bd09936ac1;
 
@@ -70,9 +80,91 @@ contract EthereumVerifierTest is Test {
                 0x0000000000000000000000000000000000000000000000000000000000000000,
                 0x7d762e1332bba77a369bee1204580472039f972d2d445d7499e814ff485fe76f,
                 0,
+                0,
+                bytes32(0),
+                0,
+                bytes32(0),
+                0,
+                bytes32(0),
+                bytes32(0),
+                bytes32(0),
+                0,
+                0,
                 0
             );
 
         proofManager.updateChainState(blockNumber, siblings, appid, state);
     }
+
+    // Same vector is used by the nexus host tests, to keep the layout in sync.
+    function testAccountStateEncodingVector() public {
+        string memory json = vm.readFile(
+            string.concat(
+                vm.projectRoot(),
+                "/test/data/account_state_encoding.json"
+            )
+        );
+
+        NexusProofManager.AccountState memory state;
+        state.statementDigest = parseBytes32(json, ".account.statement");
+        state.stateRoot = parseBytes32(json, ".account.state_root");
+        state.startNexusHash = parseBytes32(json, ".account.start_nexus_hash");
+        state.lastProofHeight = uint128(
+            parseUint(json, ".account.last_proof_height")
+        );
+        state.height = uint128(parseUint(json, ".account.height"));
+        state.owner = parseBytes32(json, ".account.owner");
+        state.nonce = uint64(parseUint(json, ".account.nonce"));
+        state.pendingStatement = parseBytes32(
+            json,
+            ".account.pending_statement"
+        );
+        state.pendingStatementHeight = uint128(
+            parseUint(json, ".account.pending_statement_height")
+        );
+        state.messageRoot = parseBytes32(json, ".account.message_root");
+        state.rollupHash = parseBytes32(json, ".account.rollup_hash");
+        state.storageHash = parseBytes32(json, ".account.storage_hash");
+        state.statementKind = uint8(parseUint(json, ".account.statement_kind"));
+        state.pendingStatementKind = uint8(
+            parseUint(json, ".account.pending_statement_kind")
+        );
+        state.generation = uint64(parseUint(json, ".account.generation"));
+
+        assertEq(abi.encode(state), vm.parseJsonBytes(json, ".encoded"));
+        assertEq(
+            sha256(abi.encode(state)),
+            parseBytes32(json, ".value_hash")
+        );
+
+        // State root of a tree with only this account.
+        uint256 blockNumber = 1;
+        bytes32 stateRoot = parseBytes32(json, ".state_root");
+        proofManager.updateNexusBlock(
+            blockNumber,
+            NexusProofManager.NexusBlock(stateRoot, stateRoot)
+        );
+        bytes32[] memory siblings;
+
+        proofManager.updateChainState(
+            blockNumber,
+            siblings,
+            parseBytes32(json, ".app_account_id"),
+            state
+        );
+    }
+
+    function parseBytes32(
+        string memory json,
+        string memory key
+    ) internal pure returns (bytes32) {
+        return abi.decode(vm.parseJson(json, key), (bytes32));
+    }
+
+    function parseUint(
+        string memory json,
+        string memory key
+    ) internal pure returns (uint256) {
+        return abi.decode(vm.parseJson(json, key), (uint256));
+    }
 }
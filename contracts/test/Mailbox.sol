@@ -152,7 +152,7 @@ contract MailBoxTest is Test {
 
     function testReceiveReceiptCallback() public {
         uint256 blockNumber = 121249;
-        bytes32 stateRoot = 0x640e68e66ba589e11f7006501a79ec882851e42fdb0e11649dd6881df3a5ed9c;
+        bytes32 stateRoot = 0x39cb0ca7f9ae9cb2216854be088a1dd44eaca6dbe48db7f3ea9575bd1255ec62;
         bytes32 blockHash = 0x640e68e66ba589e11f7006501a79ec882851e42fdb0e11649dd6881df3a5ed9c;
         bytes32 appid = 0x1f5ff885ceb5bf1350c4449316b7d703034c1278ab25bcc923d5347645a0117e;
         uint128 chainBlockNumber = 660;
@@ -178,7 +178,17 @@ contract MailBoxTest is Test {
                 0x84b2b689fba40661e61ed5e0df1ab3bc989832b218814f363249774c3a32102f,
                 0x8fbfdcd52c25ef8a2841f83a3adf19b1e0bee8b3ee7b4eff04e97319436af334,
                 121248,
-                chainBlockNumber
+                chainBlockNumber,
+                bytes32(0),
+                0,
+                bytes32(0),
+                0,
+                bytes32(0),
+                bytes32(0),
+                bytes32(0),
+                0,
+                0,
+                0
             );
 
         proofManager.updateChainState(blockNumber, siblings, appid, state);
@@ -1,59 +1,173 @@
 use crate::{
     db::NodeDB,
+    state::VmState,
     traits::NexusTransaction,
-    types::{Transaction, TransactionStatus, TransactionWithStatus},
+    types::{
+        AccountState, AppAccountId, Transaction, TransactionStatus, TransactionWithStatus,
+        TxParams, H256,
+    },
 };
 use anyhow::anyhow;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{debug, error, event, info, instrument, Level, span, warn};
 
+//Transactions too far ahead of the account nonce could otherwise fill the pool
+//without ever being executable.
+pub const MAX_NONCE_GAP: u64 = 16;
+
 #[derive(Clone)]
 pub struct Mempool {
     tx_list: Arc<Mutex<Vec<Transaction>>>,
     node_db: Arc<Mutex<NodeDB>>,
+    state: Arc<Mutex<VmState>>,
 }
 
 impl Mempool {
-    #[instrument(level = "debug", skip(node_db))]
-    pub fn new(node_db: Arc<Mutex<NodeDB>>) -> Self {
+    #[instrument(level = "debug", skip(node_db, state))]
+    pub fn new(node_db: Arc<Mutex<NodeDB>>, state: Arc<Mutex<VmState>>) -> Self {
         debug!("Creating new Mempool");
         Self {
             tx_list: Arc::new(Mutex::new(vec![])),
             node_db,
+            state,
         }
     }
 
+    //Account as of the last committed version.
+    async fn account(&self, app_id: &AppAccountId) -> Result<Option<AccountState>, anyhow::Error> {
+        let state_lock = self.state.lock().await;
+        let version = match state_lock.get_version(true)? {
+            Some(i) => i,
+            None => 0,
+        };
+
+        state_lock.get(&app_id.account_key(), version)
+    }
+
+    //Returns the transactions in pool which can be executed next, with transactions
+    //of each account ordered by nonce. Transactions are otherwise kept in arrival
+    //order, and transactions with a nonce gap stay in the pool until it is filled.
     #[instrument(level = "debug", skip(self))]
-    pub async fn get_current_txs(&self) -> (Vec<Transaction>, Option<usize>) {
+    pub async fn get_current_txs(&self) -> Vec<Transaction> {
         debug!("Getting current transactions from mempool");
-        let tx_list = self.tx_list.lock().await;
+        let tx_list: Vec<Transaction> = self.tx_list.lock().await.clone();
 
-        (
-            tx_list.clone(),
-            match tx_list.len() {
-                0 => None,
-                i => Some(i),
-            },
-        )
+        //Every slot held by an account is refilled with that account's
+        //transactions sorted by nonce, so ordering across accounts is kept.
+        let mut slots_by_account: Vec<(Vec<usize>, Vec<Transaction>)> = vec![];
+        for (index, tx) in tx_list.iter().enumerate() {
+            match slots_by_account
+                .iter_mut()
+                .find(|(_, txs)| txs[0].params.app_id() == tx.params.app_id())
+            {
+                Some((slots, txs)) => {
+                    slots.push(index);
+                    txs.push(tx.clone());
+                }
+                None => slots_by_account.push((vec![index], vec![tx.clone()])),
+            }
+        }
+
+        let mut ordered_txs: Vec<Option<Transaction>> = vec![None; tx_list.len()];
+        for (slots, mut txs) in slots_by_account {
+            txs.sort_by_key(|tx| tx.nonce);
+
+            let mut next_nonce: u64 = match self.account(txs[0].params.app_id()).await {
+                Ok(Some(account)) => account.nonce,
+                Ok(None) => 0,
+                Err(e) => {
+                    error!("Internal mempool error: {}", e);
+                    continue;
+                }
+            };
+
+            let executable_txs = txs.into_iter().take_while(|tx| {
+                if tx.nonce > next_nonce {
+                    return false;
+                }
+                next_nonce = next_nonce.max(tx.nonce.saturating_add(1));
+                true
+            });
+            for (slot, tx) in slots.into_iter().zip(executable_txs) {
+                ordered_txs[slot] = Some(tx);
+            }
+        }
+
+        ordered_txs.into_iter().flatten().collect()
     }
 
-    #[instrument(level = "debug", skip(self))]
-    pub async fn clear_upto_tx(&self, index: usize) -> () {
-        debug!("Clearing transactions up to index {} from mempool", index);
+    //Removes transactions included in a batch, others are kept in pool.
+    #[instrument(level = "debug", skip(self, txs))]
+    pub async fn clear_txs(&self, txs: &[Transaction]) -> () {
+        debug!("Clearing {} transactions from mempool", txs.len());
+        let hashes: HashSet<H256> = txs.iter().map(|tx| tx.hash()).collect();
         let mut tx_list = self.tx_list.lock().await;
-        // Clear transactions up to the specified index
-        if index < tx_list.len() {
-            tx_list.drain(0..=index);
-        } else {
-            // Handle case where index exceeds the length of tx_list
-            tx_list.clear();
-        }
+
+        tx_list.retain(|tx| !hashes.contains(&tx.hash()));
     }
 
     #[instrument(level = "debug", skip(self))]
     pub async fn add_tx(&self, tx: Transaction) -> Result<(), anyhow::Error> {
         debug!("Adding transaction to mempool");
+        let account = match self.account(tx.params.app_id()).await {
+            Ok(i) => i,
+            Err(e) => {
+                error!("Internal mempool error: {}", e);
+                return Err(anyhow!("Internal mempool error"));
+            }
+        };
+        let account_nonce: u64 = match &account {
+            Some(i) => i.nonce,
+            None => 0,
+        };
+
+        if tx.nonce < account_nonce {
+            error!(
+                "Stale nonce {} for account, current nonce is {}",
+                tx.nonce, account_nonce
+            );
+            return Err(anyhow!("Nonce already used"));
+        }
+
+        if tx.nonce > account_nonce.saturating_add(MAX_NONCE_GAP) {
+            error!(
+                "Nonce {} for account is too far ahead of current nonce {}",
+                tx.nonce, account_nonce
+            );
+            return Err(anyhow!("Nonce too far ahead"));
+        }
+
+        //Checked before nonces are reserved, so only the owner can hold them.
+        let initiated = account.as_ref().map_or(false, |i| i.is_initiated());
+        let signer: [u8; 32] = match (&tx.params, account) {
+            (TxParams::InitAccount(_), _) if initiated => {
+                error!("Init account transaction for an initiated account");
+                return Err(anyhow!("Account already initiated"));
+            }
+            (TxParams::InitAccount(params), _) => params.owner,
+            (_, Some(account)) if initiated => account.owner,
+            //Account can be initiated by a transaction still in pool.
+            _ => match self.tx_list.lock().await.iter().find_map(|i| match &i.params {
+                TxParams::InitAccount(params) if &params.app_id == tx.params.app_id() => {
+                    Some(params.owner)
+                }
+                _ => None,
+            }) {
+                Some(owner) => owner,
+                None => {
+                    error!("Transaction for an account which is not initiated");
+                    return Err(anyhow!("Account not initiated"));
+                }
+            },
+        };
+
+        if let Err(e) = tx.signature.verify(tx.nonce, &tx.params, &signer) {
+            error!("Invalid transaction signature: {}", e);
+            return Err(anyhow!("Invalid signature"));
+        }
+
         let mut node_db = self.node_db.lock().await;
         let tx_hash = tx.hash();
         match node_db.get::<TransactionWithStatus>(tx_hash.as_slice()) {
@@ -62,15 +176,25 @@ impl Mempool {
                 Err(anyhow!("Transaction already exists"))
             }
             Ok(None) => {
+                let mut tx_list = self.tx_list.lock().await;
+
+                if tx_list
+                    .iter()
+                    .any(|i| i.params.app_id() == tx.params.app_id() && i.nonce == tx.nonce)
+                {
+                    error!("Transaction with same nonce already exists in mempool");
+                    return Err(anyhow!("Nonce already used"));
+                }
+
                 node_db.put(
                     tx_hash.as_slice(),
                     &TransactionWithStatus {
                         transaction: tx.clone(),
                         status: TransactionStatus::InPool,
                         block_hash: None,
+                        error: None,
                     },
                 );
-                let mut tx_list = self.tx_list.lock().await;
 
                 tx_list.push(tx);
 
@@ -3,6 +3,7 @@ use crate::types::HeaderStore;
 use crate::types::StateUpdate;
 use crate::types::TransactionZKVM;
 use crate::zkvm::traits::ZKVMEnv;
+use crate::zkvm::ProverMode;
 use crate::zkvm_state_machine::ZKVMStateMachine;
 
 pub fn run<Z: ZKVMEnv>() {
@@ -13,8 +14,11 @@ pub fn run<Z: ZKVMEnv>() {
     let touched_states: StateUpdate = Z::read_input::<StateUpdate>().unwrap();
     let header: AvailHeader = Z::read_input::<AvailHeader>().unwrap();
     let mut header_store: HeaderStore = Z::read_input::<HeaderStore>().unwrap();
+    let prover_mode: ProverMode = Z::read_input::<ProverMode>().unwrap();
 
-    let zkvm_state_machine = ZKVMStateMachine::<Z>::new();
+    //Markers are printed by the guest, so they are left out when proving.
+    let zkvm_state_machine =
+        ZKVMStateMachine::<Z>::new().with_cycle_markers(prover_mode == ProverMode::ExecuteOnly);
     let zkvm_result = zkvm_state_machine
         .execute_batch(&header, &mut header_store, &txs, touched_states)
         .expect("Should not have panicked.");
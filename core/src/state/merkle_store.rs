@@ -1,24 +1,96 @@
 use crate::types::H256;
 use anyhow::{anyhow, Error};
-use jmt::storage::{LeafNode, Node, NodeBatch, NodeKey, TreeReader, TreeWriter};
+use jmt::storage::{LeafNode, Node, NodeBatch, NodeKey, StaleNodeIndex, TreeReader, TreeWriter};
 use jmt::{KeyHash, OwnedValue, Version};
-use rocksdb::DB;
+use lru::LruCache;
+use rocksdb::{Direction, IteratorMode, DB};
 use rocksdb::{WriteBatch, WriteOptions};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{from_slice, to_vec};
-use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
+
+const NODE_PREFIX: &[u8] = b"jmt-node";
+const VALUE_PREFIX: &[u8] = b"jmt-value";
+const STALE_PREFIX: &[u8] = b"jmt-stale";
+const LAYOUT_KEY: &[u8] = b"value-layout";
+//1: Version keyed values, 2: Binary encoded nodes and values.
+const LAYOUT_VERSION: u8 = 2;
+const NODE_CACHE_CAPACITY: usize = 1 << 16;
+const RIGHTMOST_LEAF_KEY: &[u8] = b"rightmost-leaf";
+
+fn value_prefix(key_hash: &KeyHash) -> Vec<u8> {
+    [VALUE_PREFIX, &key_hash.0].concat()
+}
+
+fn encode_node_key(node_key: &NodeKey) -> Result<Vec<u8>, Error> {
+    Ok([NODE_PREFIX, &bincode::serialize(node_key)?].concat())
+}
+
+//Values are keyed by (key_hash, !version) so the latest version sorts first.
+fn value_key(key_hash: &KeyHash, version: Version) -> Vec<u8> {
+    [value_prefix(key_hash).as_slice(), &(!version).to_be_bytes()].concat()
+}
+
+//Stale nodes are keyed by the version they became stale at, so they can be pruned in order.
+fn stale_node_key(stale_node: &StaleNodeIndex) -> Result<Vec<u8>, Error> {
+    Ok([
+        STALE_PREFIX,
+        &stale_node.stale_since_version.to_be_bytes(),
+        &bincode::serialize(&stale_node.node_key)?,
+    ]
+    .concat())
+}
+
+//Latest version of a value at or below max_version, with None values for deletions.
+fn latest_value(
+    db: &DB,
+    key_hash: &KeyHash,
+    max_version: Version,
+) -> Result<Option<(Version, Option<OwnedValue>)>, Error> {
+    let prefix = value_prefix(key_hash);
+
+    //Versions are inverted in the key, so the first entry found from
+    //max_version onwards is the latest value at or below it.
+    let mut iter = db.iterator(IteratorMode::From(
+        &value_key(key_hash, max_version),
+        Direction::Forward,
+    ));
+
+    match iter.next() {
+        Some(Ok((key, value))) if key.starts_with(&prefix) => {
+            let mut inverted_version = [0u8; 8];
+            inverted_version.copy_from_slice(&key[prefix.len()..]);
+
+            Ok(Some((
+                !Version::from_be_bytes(inverted_version),
+                bincode::deserialize(&value)?,
+            )))
+        }
+        Some(Err(e)) => Err(anyhow!(e.to_string())),
+        _ => Ok(None),
+    }
+}
+
 //Store to be used inside StateMachine to store Merkle Tree.
 #[derive(Clone)]
 pub struct MerkleStore {
     db: Arc<Mutex<DB>>,
     cache: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>,
+    //Nodes are immutable once written, so cached nodes never go stale.
+    nodes: Arc<Mutex<LruCache<NodeKey, Node>>>,
 }
 
 impl MerkleStore {
     pub fn with_db(db: Arc<Mutex<DB>>, cache: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>) -> Self {
-        MerkleStore { db, cache }
+        MerkleStore {
+            db,
+            cache,
+            nodes: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(NODE_CACHE_CAPACITY).unwrap(),
+            ))),
+        }
     }
 
     pub fn get<V: DeserializeOwned>(
@@ -140,6 +212,210 @@ impl MerkleStore {
         Ok(())
     }
 
+    //Moves entries of older layouts to the current one, values stored as a vector
+    //of versions under the raw key hash and json encoded nodes and values.
+    //Returns the number of entries migrated.
+    pub fn migrate_layout(&self) -> Result<usize, Error> {
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+
+        let layout: u8 = match db.get(LAYOUT_KEY) {
+            Ok(Some(i)) => i.first().copied().unwrap_or(0),
+            Ok(None) => 0,
+            Err(e) => return Err(anyhow!(e.to_string())),
+        };
+        if layout == LAYOUT_VERSION {
+            return Ok(0);
+        }
+
+        let mut batch = WriteBatch::default();
+        let mut migrated: usize = 0;
+
+        for item in db.iterator(IteratorMode::Start) {
+            let (key, value) = item.map_err(|e| anyhow!(e.to_string()))?;
+
+            //Only the old value entries were keyed by the bare 32 byte key hash.
+            if key.len() == 32 {
+                let values: Vec<(Version, OwnedValue)> = match from_slice(&value) {
+                    Ok(i) => i,
+                    Err(_) => continue,
+                };
+                let mut key_hash = [0u8; 32];
+                key_hash.copy_from_slice(&key);
+
+                for (version, value) in values {
+                    let serialized_value = bincode::serialize(&Some(value))?;
+                    batch.put(value_key(&KeyHash(key_hash), version), serialized_value);
+                }
+                batch.delete(key);
+                migrated += 1;
+            } else if key.starts_with(VALUE_PREFIX) && layout == 1 {
+                let value: Option<OwnedValue> = from_slice(&value)?;
+                batch.put(key, bincode::serialize(&value)?);
+                migrated += 1;
+            } else if key.starts_with(b"{") {
+                //Json encoded node keys.
+                let node_key: NodeKey = match from_slice(&key) {
+                    Ok(i) => i,
+                    Err(_) => continue,
+                };
+                let node: Node = from_slice(&value)?;
+
+                batch.put(encode_node_key(&node_key)?, bincode::serialize(&node)?);
+                batch.delete(key);
+                migrated += 1;
+            }
+        }
+
+        batch.put(LAYOUT_KEY, [LAYOUT_VERSION]);
+        db.write_opt(batch, &WriteOptions::default())
+            .map_err(|e| anyhow!(e))?;
+
+        Ok(migrated)
+    }
+
+    //Writes values of a version directly, used when the nodes are written by a restore.
+    pub fn write_values(
+        &self,
+        version: Version,
+        values: &[(KeyHash, OwnedValue)],
+    ) -> Result<(), Error> {
+        let mut batch = WriteBatch::default();
+
+        for (key_hash, value) in values {
+            let serialized_value = bincode::serialize(&Some(value))?;
+            batch.put(value_key(key_hash, version), serialized_value);
+        }
+
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+        db.write_opt(batch, &WriteOptions::default())
+            .map_err(|e| anyhow!(e))?;
+
+        Ok(())
+    }
+
+    //Deletes all nodes and values along with the rightmost leaf index, left
+    //behind by a restore that was interrupted before its version was set.
+    pub fn clear_tree(&self) -> Result<(), Error> {
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+
+        let mut batch = WriteBatch::default();
+        for prefix in [NODE_PREFIX, VALUE_PREFIX, STALE_PREFIX] {
+            for item in db.iterator(IteratorMode::From(prefix, Direction::Forward)) {
+                let (key, _) = item.map_err(|e| anyhow!(e.to_string()))?;
+                if !key.starts_with(prefix) {
+                    break;
+                }
+
+                batch.delete(key);
+            }
+        }
+        batch.delete(RIGHTMOST_LEAF_KEY);
+
+        db.write_opt(batch, &WriteOptions::default())
+            .map_err(|e| anyhow!(e))?;
+        drop(db);
+
+        let mut nodes = match self.nodes.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+        nodes.clear();
+
+        Ok(())
+    }
+
+    pub fn write_stale_node_index<'a>(
+        &self,
+        stale_nodes: impl IntoIterator<Item = &'a StaleNodeIndex>,
+    ) -> Result<(), Error> {
+        let mut batch = WriteBatch::default();
+
+        for stale_node in stale_nodes {
+            batch.put(stale_node_key(stale_node)?, b"");
+        }
+
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+        db.write_opt(batch, &WriteOptions::default())
+            .map_err(|e| anyhow!(e))?;
+
+        Ok(())
+    }
+
+    //Deletes nodes that became stale at or before min_version, along with the
+    //values only they referenced. Returns the number of nodes pruned.
+    pub fn prune(&self, min_version: Version) -> Result<usize, Error> {
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+
+        let mut batch = WriteBatch::default();
+        let mut pruned: Vec<NodeKey> = vec![];
+
+        for item in db.iterator(IteratorMode::From(STALE_PREFIX, Direction::Forward)) {
+            let (key, _) = item.map_err(|e| anyhow!(e.to_string()))?;
+            if !key.starts_with(STALE_PREFIX) {
+                break;
+            }
+
+            let mut stale_since_version = [0u8; 8];
+            stale_since_version.copy_from_slice(&key[STALE_PREFIX.len()..STALE_PREFIX.len() + 8]);
+            let stale_since_version = Version::from_be_bytes(stale_since_version);
+            if stale_since_version > min_version {
+                break;
+            }
+
+            let node_key: NodeKey = bincode::deserialize(&key[STALE_PREFIX.len() + 8..])?;
+            let serialized_node_key = encode_node_key(&node_key)?;
+
+            //A leaf may have been moved without its value being rewritten, so the
+            //value is only deleted when a newer one replaced it.
+            if let Some(i) = db.get(&serialized_node_key)? {
+                if let Node::Leaf(leaf) = bincode::deserialize(&i)? {
+                    let key_hash = leaf.key_hash();
+                    let referenced = latest_value(&db, &key_hash, node_key.version())?;
+                    let latest = latest_value(&db, &key_hash, stale_since_version)?;
+
+                    if let (Some((referenced, _)), Some((latest, _))) = (referenced, latest) {
+                        if latest > referenced {
+                            batch.delete(value_key(&key_hash, referenced));
+                        }
+                    }
+                }
+            }
+
+            batch.delete(serialized_node_key);
+            batch.delete(key);
+            pruned.push(node_key);
+        }
+
+        db.write_opt(batch, &WriteOptions::default())
+            .map_err(|e| anyhow!(e))?;
+        drop(db);
+
+        let mut nodes = match self.nodes.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+        for node_key in pruned.iter() {
+            nodes.pop(node_key);
+        }
+
+        Ok(pruned.len())
+    }
+
     pub fn clear_cache(&mut self) -> Result<(), Error> {
         let mut cache = match self.cache.lock() {
             Ok(i) => i,
@@ -152,14 +428,48 @@ impl MerkleStore {
 
 impl TreeReader for MerkleStore {
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node>, anyhow::Error> {
-        match self.get(&to_vec(node_key)?, true) {
-            Ok(i) => Ok(i),
-            Err(e) => Err(anyhow!(e)),
+        //Only one of the locks is held at a time, so readers and writers cannot deadlock.
+        {
+            let mut nodes = match self.nodes.lock() {
+                Ok(i) => i,
+                Err(e) => return Err(anyhow!("No lock obtained.")),
+            };
+
+            if let Some(node) = nodes.get(node_key) {
+                return Ok(Some(node.clone()));
+            }
+        }
+
+        let serialized_node = {
+            let db = match self.db.lock() {
+                Ok(i) => i,
+                Err(e) => return Err(anyhow!("No lock obtained.")),
+            };
+
+            match db.get(encode_node_key(node_key)?) {
+                Ok(i) => i,
+                Err(e) => return Err(anyhow!(e.to_string())),
+            }
+        };
+
+        match serialized_node {
+            Some(i) => {
+                let node: Node = bincode::deserialize(&i)?;
+                let mut nodes = match self.nodes.lock() {
+                    Ok(i) => i,
+                    Err(e) => return Err(anyhow!("No lock obtained.")),
+                };
+                nodes.put(node_key.clone(), node.clone());
+
+                Ok(Some(node))
+            }
+            None => Ok(None),
         }
     }
 
+    //Index is maintained on writes, so restores do not have to scan all leaves.
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>, anyhow::Error> {
-        todo!("StateDB does not support [`TreeReader::get_rightmost_leaf`] yet")
+        self.get(RIGHTMOST_LEAF_KEY, true)
     }
 
     fn get_value_option(
@@ -167,90 +477,57 @@ impl TreeReader for MerkleStore {
         max_version: Version,
         key_hash: KeyHash,
     ) -> Result<Option<OwnedValue>, anyhow::Error> {
-        let values: Vec<(Version, OwnedValue)> = match self.get(&key_hash.0, true) {
-            Ok(Some(i)) => i,
-            Ok(None) => {
-                return Ok(None);
-            }
-            Err(e) => return Err(anyhow!(e)),
+        let db = match self.db.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
         };
-        let mut found_value: Option<(Version, OwnedValue)> = None;
-
-        //TODO: Change this logic to avoid the iteration.
-        values.into_iter().for_each(|(version, value)| {
-            if version <= max_version {
-                match found_value {
-                    Some((found_version, _)) => {
-                        if found_version < version {
-                            found_value = Some((version, value))
-                        }
-                    }
-                    None => found_value = Some((version, value)),
-                }
-            }
-        });
 
-        Ok(match found_value {
-            Some(i) => Some(i.1),
-            None => None,
-        })
+        match latest_value(&db, &key_hash, max_version)? {
+            Some((_, value)) => Ok(value),
+            None => Ok(None),
+        }
     }
 }
 
-//TODO: Optimise the storage of all version values as a vector to avoid loops.
 impl TreeWriter for MerkleStore {
     fn write_node_batch(&self, node_batch: &NodeBatch) -> Result<(), anyhow::Error> {
         let mut batch = WriteBatch::default();
 
+        let mut rightmost_leaf: Option<(NodeKey, LeafNode)> = self.get_rightmost_leaf()?;
+        let mut rightmost_leaf_updated = false;
+
         // Add nodes to the batch
         for (node_key, node) in node_batch.nodes() {
-            let serialized_key = to_vec(node_key).map_err(|e| anyhow!(e))?;
-            let serialized_value = to_vec(node).map_err(|e| anyhow!(e))?;
+            let serialized_key = encode_node_key(node_key)?;
+            let serialized_value = bincode::serialize(node)?;
             batch.put(serialized_key, serialized_value);
-        }
-
-        let mut updates: BTreeMap<KeyHash, Vec<(Version, Option<OwnedValue>)>> = BTreeMap::new();
-        for ((version, key_hash), value) in node_batch.values() {
-            updates
-                .entry(key_hash.clone())
-                .or_default()
-                .push((*version, value.clone()));
-        }
-
-        // Process each key_hash
-        for (key_hash, changes) in updates {
-            // Retrieve existing values from the database
-            let mut existing_values: Vec<(Version, OwnedValue)> = match self.get(&key_hash.0, true)
-            {
-                Ok(Some(values)) => values,
-                Ok(None) => Vec::new(),
-                Err(e) => return Err(anyhow!(e)),
-            };
 
-            // Apply changes
-            for (version, value) in changes {
-                if let Some(val) = value {
-                    // Check if the version already exists
-                    if let Some(existing) = existing_values.iter_mut().find(|(v, _)| v == &version)
-                    {
-                        // Replace the existing value with the new value
-                        existing.1 = val;
-                    } else {
-                        // Add the new version and value
-                        existing_values.push((version, val));
+            //The rightmost leaf is tracked within the latest version written,
+            //as restores write all nodes at a single version.
+            if let Node::Leaf(leaf) = node {
+                let is_rightmost = match &rightmost_leaf {
+                    Some((key, current)) => {
+                        (node_key.version(), leaf.key_hash()) > (key.version(), current.key_hash())
                     }
-                } else {
-                    // If value is None, remove the entry with the specified version
-                    existing_values.retain(|(v, _)| v != &version);
+                    None => true,
+                };
+
+                if is_rightmost {
+                    rightmost_leaf = Some((node_key.clone(), leaf.clone()));
+                    rightmost_leaf_updated = true;
                 }
             }
+        }
 
-            // Sort values in descending order by version
-            existing_values.sort_by(|a, b| b.0.cmp(&a.0));
+        if let (true, Some(leaf)) = (rightmost_leaf_updated, &rightmost_leaf) {
+            let serialized_value = to_vec(leaf).map_err(|e| anyhow!(e))?;
+            batch.put(RIGHTMOST_LEAF_KEY, serialized_value);
+        }
 
-            // Serialize and insert the updated array
-            let serialized_value = to_vec(&existing_values).map_err(|e| anyhow!(e))?;
-            batch.put(key_hash.0, serialized_value);
+        // Each version of a value is stored under its own key
+        for ((version, key_hash), value) in node_batch.values() {
+            let serialized_value = bincode::serialize(value)?;
+            batch.put(value_key(key_hash, *version), serialized_value);
         }
 
         let db = match self.db.lock() {
@@ -260,6 +537,15 @@ impl TreeWriter for MerkleStore {
         // Write the batch atomically
         db.write_opt(batch, &WriteOptions::default())
             .map_err(|e| anyhow!(e))?;
+        drop(db);
+
+        let mut nodes = match self.nodes.lock() {
+            Ok(i) => i,
+            Err(e) => return Err(anyhow!("No lock obtained.")),
+        };
+        for (node_key, node) in node_batch.nodes() {
+            nodes.put(node_key.clone(), node.clone());
+        }
 
         Ok(())
     }
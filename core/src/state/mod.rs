@@ -8,4 +8,7 @@ pub use self::merkle_store::*;
 #[cfg(any(feature = "native"))]
 pub use self::vm_state::VmState;
 
+pub mod multiproof;
 pub mod types;
+
+pub use self::multiproof::MultiProof;
@@ -0,0 +1,266 @@
+use crate::types::H256;
+use crate::utils::hasher::{Digest, Sha256};
+use anyhow::{anyhow, Error};
+#[cfg(any(feature = "native"))]
+use jmt::proof::SparseMerkleProof;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+//Same domain separators and placeholder as jmt, so computed roots match the tree.
+const LEAF_DOMAIN_SEPARATOR: &[u8] = b"JMT::LeafNode";
+const INTERNAL_DOMAIN_SEPARATOR: &[u8] = b"JMT::IntrnalNode";
+const SPARSE_MERKLE_PLACEHOLDER_HASH: [u8; 32] = *b"SPARSE_MERKLE_PLACEHOLDER_HASH__";
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MultiProofLeaf {
+    pub key: [u8; 32],
+    pub depth: u16,
+    //Key hash and value hash of the leaf found at the position of the key.
+    pub leaf: Option<([u8; 32], [u8; 32])>,
+}
+
+//Proof for a set of keys against a single root. Internal nodes shared by the
+//paths of the keys are computed once, and only siblings which cannot be computed
+//from other paths are included.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MultiProof {
+    pub leaves: Vec<MultiProofLeaf>,
+    //Ordered as they are consumed when computing the root.
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MultiProof {
+    #[cfg(any(feature = "native"))]
+    pub fn new(proofs: Vec<([u8; 32], SparseMerkleProof<Sha256>)>) -> Result<Self, Error> {
+        let mut available_siblings: HashMap<(usize, [u8; 32]), [u8; 32]> = HashMap::new();
+        let mut leaves: Vec<MultiProofLeaf> = vec![];
+
+        for (key, proof) in proofs.iter() {
+            let depth = proof.siblings().len();
+
+            //Siblings in jmt proofs are ordered from root to leaf.
+            for (index, sibling) in proof.siblings().iter().enumerate() {
+                available_siblings.insert(
+                    (index + 1, sibling_path(&path_at(key, index + 1), index + 1)),
+                    sibling.hash::<Sha256>(),
+                );
+            }
+
+            leaves.push(MultiProofLeaf {
+                key: key.clone(),
+                depth: depth as u16,
+                leaf: proof
+                    .leaf()
+                    .map(|leaf| (leaf.key_hash().0, leaf.value_hash().0)),
+            });
+        }
+
+        let mut siblings: Vec<[u8; 32]> = vec![];
+        compute_root(&leaves, |depth, path| {
+            match available_siblings.get(&(depth, path.clone())) {
+                Some(i) => {
+                    siblings.push(i.clone());
+                    Ok(i.clone())
+                }
+                None => Err(anyhow!("Sibling missing in merkle proofs.")),
+            }
+        })?;
+
+        Ok(Self { leaves, siblings })
+    }
+
+    pub fn verify(
+        &self,
+        expected_root: &H256,
+        values: &HashMap<[u8; 32], Option<Vec<u8>>>,
+    ) -> Result<(), Error> {
+        //Keys of the leaves must be exactly the keys of the values, otherwise
+        //values of some keys would be taken without being proven.
+        let mut keys: HashSet<[u8; 32]> = HashSet::new();
+        for leaf in self.leaves.iter() {
+            if !keys.insert(leaf.key.clone()) {
+                return Err(anyhow!("Duplicate key in multiproof."));
+            }
+
+            let value = match values.get(&leaf.key) {
+                Some(i) => i,
+                None => return Err(anyhow!("Multiproof has a key without a value.")),
+            };
+
+            verify_leaf(leaf, value)?;
+        }
+
+        if values.keys().any(|key| !keys.contains(key)) {
+            return Err(anyhow!("Multiproof does not cover all keys."));
+        }
+
+        let mut siblings = self.siblings.iter();
+        let root = compute_root(&self.leaves, |_, _| match siblings.next() {
+            Some(i) => Ok(i.clone()),
+            None => Err(anyhow!("Sibling missing in multiproof.")),
+        })?;
+
+        if siblings.next().is_some() {
+            return Err(anyhow!("Unused siblings in multiproof."));
+        }
+
+        //Empty tree root is reported as zero by the node.
+        if root == SPARSE_MERKLE_PLACEHOLDER_HASH && expected_root == &H256::zero() {
+            return Ok(());
+        }
+
+        if &H256::from(root) != expected_root {
+            return Err(anyhow!("Multiproof root does not match expected root."));
+        }
+
+        Ok(())
+    }
+}
+
+fn verify_leaf(leaf: &MultiProofLeaf, value: &Option<Vec<u8>>) -> Result<(), Error> {
+    if leaf.depth as usize > MAX_DEPTH {
+        return Err(anyhow!("Invalid leaf depth in multiproof."));
+    }
+
+    match (value, &leaf.leaf) {
+        (Some(value), Some((key_hash, value_hash))) => {
+            if key_hash != &leaf.key {
+                return Err(anyhow!("Leaf key does not match in multiproof."));
+            }
+            if value_hash != &hash_value(value) {
+                return Err(anyhow!("Leaf value does not match in multiproof."));
+            }
+        }
+        (Some(_), None) => return Err(anyhow!("Expected inclusion, found empty leaf.")),
+        //Non inclusion can be shown by another leaf sharing the path up to the depth.
+        (None, Some((key_hash, _))) => {
+            if key_hash == &leaf.key
+                || path_at(key_hash, leaf.depth as usize) != path_at(&leaf.key, leaf.depth as usize)
+            {
+                return Err(anyhow!("Invalid non inclusion leaf in multiproof."));
+            }
+        }
+        (None, None) => (),
+    }
+
+    Ok(())
+}
+
+//Computes the root bottom up, a level at a time. `sibling` is called for every
+//sibling which is not on the path of any of the leaves, in a deterministic order.
+fn compute_root<F>(leaves: &[MultiProofLeaf], mut sibling: F) -> Result<[u8; 32], Error>
+where
+    F: FnMut(usize, &[u8; 32]) -> Result<[u8; 32], Error>,
+{
+    let mut levels: Vec<BTreeMap<[u8; 32], [u8; 32]>> = vec![BTreeMap::new(); MAX_DEPTH + 1];
+
+    for leaf in leaves.iter() {
+        let depth = leaf.depth as usize;
+        if depth > MAX_DEPTH {
+            return Err(anyhow!("Invalid leaf depth in multiproof."));
+        }
+
+        let hash = match &leaf.leaf {
+            Some((key_hash, value_hash)) => hash_leaf(key_hash, value_hash),
+            None => SPARSE_MERKLE_PLACEHOLDER_HASH,
+        };
+
+        //Multiple keys can resolve to the same leaf, when proving non inclusion.
+        match levels[depth].insert(path_at(&leaf.key, depth), hash) {
+            Some(existing) if existing != hash => {
+                return Err(anyhow!("Conflicting leaves in multiproof."))
+            }
+            _ => (),
+        }
+    }
+
+    for depth in (1..=MAX_DEPTH).rev() {
+        let level = std::mem::take(&mut levels[depth]);
+        //Left child is always ordered right before its right sibling.
+        let mut nodes = level.iter().peekable();
+
+        while let Some((path, hash)) = nodes.next() {
+            let is_right = bit(path, depth - 1);
+            let sibling_path = sibling_path(path, depth);
+
+            let sibling_hash = match nodes.peek() {
+                Some((next_path, next_hash)) if !is_right && **next_path == sibling_path => {
+                    let next_hash = **next_hash;
+                    nodes.next();
+                    next_hash
+                }
+                _ => sibling(depth, &sibling_path)?,
+            };
+
+            let parent_hash = if is_right {
+                hash_internal(&sibling_hash, hash)
+            } else {
+                hash_internal(hash, &sibling_hash)
+            };
+
+            if levels[depth - 1]
+                .insert(path_at(path, depth - 1), parent_hash)
+                .is_some()
+            {
+                return Err(anyhow!(
+                    "Leaf found on the path of another key in multiproof."
+                ));
+            }
+        }
+    }
+
+    match levels[0].get(&[0u8; 32]) {
+        Some(i) => Ok(i.clone()),
+        None => Err(anyhow!("Empty multiproof.")),
+    }
+}
+
+fn bit(path: &[u8; 32], index: usize) -> bool {
+    (path[index / 8] >> (7 - index % 8)) & 1 == 1
+}
+
+//Path of the node at the given depth on the way to the key, with all bits
+//below the depth set to zero.
+fn path_at(key: &[u8; 32], depth: usize) -> [u8; 32] {
+    let mut path = [0u8; 32];
+    for index in 0..depth {
+        if bit(key, index) {
+            path[index / 8] |= 1 << (7 - index % 8);
+        }
+    }
+
+    path
+}
+
+fn sibling_path(path: &[u8; 32], depth: usize) -> [u8; 32] {
+    let mut sibling = path.clone();
+    sibling[(depth - 1) / 8] ^= 1 << (7 - (depth - 1) % 8);
+
+    sibling
+}
+
+fn hash_value(value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(value);
+
+    hasher.finalize().into()
+}
+
+fn hash_leaf(key_hash: &[u8; 32], value_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(LEAF_DOMAIN_SEPARATOR);
+    hasher.update(key_hash);
+    hasher.update(value_hash);
+
+    hasher.finalize().into()
+}
+
+fn hash_internal(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(INTERNAL_DOMAIN_SEPARATOR);
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher.finalize().into()
+}
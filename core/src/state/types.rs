@@ -1,15 +1,28 @@
 use crate::utils::hasher::Sha256;
-use ethabi::{decode, encode, ParamType, Token};
+use ethabi::{decode, encode, ParamType, Token, Uint};
 use parity_scale_codec::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 #[cfg(any(feature = "native"))]
 use utoipa::ToSchema;
 
-
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
 #[cfg_attr(feature = "native", derive(ToSchema))]
 pub struct StatementDigest(pub [u32; 8]);
 
+//Verifier a proof is checked with. Statements of accounts are bound to a kind,
+//which has to be supported by the zkvm nexus runs in. Proofs are verified by
+//recursion, so only the recursion kind of each zkvm is defined. Groth16 and plonk
+//proofs would need a verifier running inside the guest, and are not accepted.
+//Declared in code order, so scale and serde encodings match the codes.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub enum ProofKind {
+    Risc0Receipt,
+    Sp1Compressed,
+    //Public values of a natively executed guest, only for testing.
+    Mock,
+}
+
 //TODO: Need to check PartialEq to Eq difference, to ensure there is not security vulnerability.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "native", derive(ToSchema))]
@@ -19,6 +32,20 @@ pub struct AccountState {
     pub start_nexus_hash: [u8; 32],
     pub last_proof_height: u32,
     pub height: u32,
+    pub owner: [u8; 32],
+    pub nonce: u64,
+    pub pending_statement: StatementDigest,
+    pub pending_statement_height: u32,
+    pub message_root: [u8; 32],
+    //Data commitment of the rollup at the latest proven height.
+    pub rollup_hash: [u8; 32],
+    //Hash of storage writes authorised by the latest proof, cleared once applied.
+    pub storage_hash: [u8; 32],
+    pub statement_kind: ProofKind,
+    pub pending_statement_kind: ProofKind,
+    //Number of times the account was closed. Storage slots are keyed by it, so
+    //slots of a closed account are not seen once it is initiated again.
+    pub generation: u64,
 }
 
 impl AccountState {
@@ -29,9 +56,40 @@ impl AccountState {
             start_nexus_hash: [0; 32],
             last_proof_height: 0,
             height: 0,
+            owner: [0; 32],
+            nonce: 0,
+            pending_statement: StatementDigest::zero(),
+            pending_statement_height: 0,
+            message_root: [0; 32],
+            rollup_hash: [0; 32],
+            storage_hash: [0; 32],
+            statement_kind: ProofKind::Risc0Receipt,
+            pending_statement_kind: ProofKind::Risc0Receipt,
+            generation: 0,
         }
     }
 
+    //Leaf kept in the tree for a closed account, with only the fields carried
+    //over to the next time the account is initiated.
+    pub fn closed(&self) -> Self {
+        let mut closed = Self::zero();
+        //Every close is a transaction of the account, so the generation cannot
+        //overflow in practice.
+        closed.generation = self.generation + 1;
+        //Kept so txs signed before the close cannot be replayed after the next init.
+        closed.nonce = self.nonce;
+
+        closed
+    }
+
+    pub fn is_initiated(&self) -> bool {
+        let mut empty = Self::zero();
+        empty.generation = self.generation;
+        empty.nonce = self.nonce;
+
+        self != &empty
+    }
+
     pub fn encode(&self) -> Vec<u8> {
         let tokens = vec![
             self.statement.encode(),
@@ -39,6 +97,16 @@ impl AccountState {
             Token::FixedBytes(self.start_nexus_hash.to_vec()),
             Token::Uint(self.last_proof_height.into()),
             Token::Uint(self.height.into()),
+            Token::FixedBytes(self.owner.to_vec()),
+            Token::Uint(self.nonce.into()),
+            self.pending_statement.encode(),
+            Token::Uint(self.pending_statement_height.into()),
+            Token::FixedBytes(self.message_root.to_vec()),
+            Token::FixedBytes(self.rollup_hash.to_vec()),
+            Token::FixedBytes(self.storage_hash.to_vec()),
+            self.statement_kind.encode(),
+            self.pending_statement_kind.encode(),
+            Token::Uint(self.generation.into()),
         ];
         encode(&tokens)
     }
@@ -51,11 +119,21 @@ impl AccountState {
                 ParamType::FixedBytes(32),
                 ParamType::Uint(32),
                 ParamType::Uint(32),
+                ParamType::FixedBytes(32),
+                ParamType::Uint(64),
+                ParamType::FixedBytes(32),
+                ParamType::Uint(32),
+                ParamType::FixedBytes(32),
+                ParamType::FixedBytes(32),
+                ParamType::FixedBytes(32),
+                ParamType::Uint(8),
+                ParamType::Uint(8),
+                ParamType::Uint(64),
             ],
             encoded,
         )?;
 
-        if tokens.len() != 5 {
+        if tokens.len() != 15 {
             return Err(ethabi::Error::InvalidData);
         }
 
@@ -82,6 +160,48 @@ impl AccountState {
             .into_uint()
             .ok_or(ethabi::Error::InvalidData)?
             .as_u32();
+        let owner: [u8; 32] = tokens[5]
+            .clone()
+            .into_fixed_bytes()
+            .ok_or(ethabi::Error::InvalidData)?
+            .try_into()
+            .map_err(|_| ethabi::Error::InvalidData)?;
+        let nonce = tokens[6]
+            .clone()
+            .into_uint()
+            .ok_or(ethabi::Error::InvalidData)?
+            .as_u64();
+        let pending_statement = StatementDigest::decode(&tokens[7])?;
+        let pending_statement_height = tokens[8]
+            .clone()
+            .into_uint()
+            .ok_or(ethabi::Error::InvalidData)?
+            .as_u32();
+        let message_root: [u8; 32] = tokens[9]
+            .clone()
+            .into_fixed_bytes()
+            .ok_or(ethabi::Error::InvalidData)?
+            .try_into()
+            .map_err(|_| ethabi::Error::InvalidData)?;
+        let rollup_hash: [u8; 32] = tokens[10]
+            .clone()
+            .into_fixed_bytes()
+            .ok_or(ethabi::Error::InvalidData)?
+            .try_into()
+            .map_err(|_| ethabi::Error::InvalidData)?;
+        let storage_hash: [u8; 32] = tokens[11]
+            .clone()
+            .into_fixed_bytes()
+            .ok_or(ethabi::Error::InvalidData)?
+            .try_into()
+            .map_err(|_| ethabi::Error::InvalidData)?;
+        let statement_kind = ProofKind::decode(&tokens[12])?;
+        let pending_statement_kind = ProofKind::decode(&tokens[13])?;
+        let generation = tokens[14]
+            .clone()
+            .into_uint()
+            .ok_or(ethabi::Error::InvalidData)?
+            .as_u64();
 
         Ok(AccountState {
             statement,
@@ -89,6 +209,16 @@ impl AccountState {
             start_nexus_hash,
             last_proof_height,
             height,
+            owner,
+            nonce,
+            pending_statement,
+            pending_statement_height,
+            message_root,
+            rollup_hash,
+            storage_hash,
+            statement_kind,
+            pending_statement_kind,
+            generation,
         })
     }
 }
@@ -122,9 +252,55 @@ impl StatementDigest {
 }
 
 impl StatementDigest {
-    fn zero() -> Self {
+    pub fn zero() -> Self {
         Self([0u32; 8])
     }
+
+    //Image id as bytes, in the word order verifiers take it in.
+    pub fn as_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_mut(4).zip(self.0.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        bytes
+    }
+}
+
+impl ProofKind {
+    //Codes are part of the account state encoding, so existing codes must not change.
+    pub fn code(&self) -> u8 {
+        match self {
+            ProofKind::Risc0Receipt => 0,
+            ProofKind::Sp1Compressed => 1,
+            ProofKind::Mock => 2,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(ProofKind::Risc0Receipt),
+            1 => Some(ProofKind::Sp1Compressed),
+            2 => Some(ProofKind::Mock),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self) -> Token {
+        Token::Uint(self.code().into())
+    }
+
+    pub fn decode(token: &Token) -> Result<Self, ethabi::Error> {
+        if let Token::Uint(value) = token {
+            if *value > Uint::from(u8::MAX) {
+                return Err(ethabi::Error::InvalidData);
+            }
+
+            ProofKind::from_code(value.low_u32() as u8).ok_or(ethabi::Error::InvalidData)
+        } else {
+            Err(ethabi::Error::InvalidData)
+        }
+    }
 }
 
 // impl From<RiscZeroDigest> for StatementDigest {
@@ -1,16 +1,18 @@
 use crate::types::H256;
 use crate::utils::hasher::Sha256;
 use crate::{
-    state::{types::AccountState, MerkleStore},
+    state::{types::AccountState, MerkleStore, MultiProof},
     traits::Leaf,
     types::{AppAccountId, StateUpdate},
     utils::hasher::ShaHasher,
 };
 use anyhow::{anyhow, Error};
 use jmt::{
-    proof::SparseMerkleProof,
-    storage::{NodeBatch, TreeUpdateBatch, TreeWriter},
-    JellyfishMerkleTree, KeyHash, SimpleHasher, Version,
+    proof::{SparseMerkleProof, SparseMerkleRangeProof},
+    restore::JellyfishMerkleRestore,
+    storage::{NodeBatch, StaleNodeIndex, TreeReader, TreeUpdateBatch, TreeWriter},
+    JellyfishMerkleIterator, JellyfishMerkleTree, KeyHash, OwnedValue, RootHash, SimpleHasher,
+    Version,
 };
 use rocksdb::{Options, DB};
 use std::{
@@ -21,6 +23,7 @@ use std::{
 
 pub struct VmState {
     merkle_store: MerkleStore,
+    pruning: bool,
 }
 
 impl VmState {
@@ -34,8 +37,14 @@ impl VmState {
         let db_arc = Arc::new(Mutex::new(db));
         let cache_arc = Arc::new(Mutex::new(cache));
         let merkle_store = MerkleStore::with_db(db_arc.clone(), cache_arc.clone());
+        merkle_store
+            .migrate_layout()
+            .expect("unable to migrate the state db to the current layout.");
 
-        Self { merkle_store }
+        Self {
+            merkle_store,
+            pruning: false,
+        }
     }
 
     pub fn get_version(&self, committed: bool) -> Result<Option<u64>, anyhow::Error> {
@@ -61,38 +70,63 @@ impl VmState {
         set: HashMap<H256, Option<AccountState>>,
         version: Version,
     ) -> Result<(TreeUpdateBatch, StateUpdate), Error> {
-        let mut pre_state: HashMap<[u8; 32], (Option<AccountState>, SparseMerkleProof<Sha256>)> =
-            HashMap::new();
+        self.update_set_with_storage(set, HashMap::new(), version)
+    }
+
+    //Storage slots are written without a pre state proof, as their previous
+    //values are not read by the state transition function.
+    pub fn update_set_with_storage(
+        &mut self,
+        set: HashMap<H256, Option<AccountState>>,
+        storage: HashMap<H256, Option<H256>>,
+        version: Version,
+    ) -> Result<(TreeUpdateBatch, StateUpdate), Error> {
+        let mut pre_state: HashMap<[u8; 32], Option<AccountState>> = HashMap::new();
+        let mut proofs: Vec<([u8; 32], SparseMerkleProof<Sha256>)> = vec![];
         let prev_version: u64 = version - 1;
         let pre_state_root = self.get_root(prev_version)?;
 
         set.iter()
             .try_for_each::<_, Result<(), anyhow::Error>>(|(key, account)| {
                 //Note: Do not have to get version minus one, as any version lesser than equal to is supposed to be retrieved, but need to debug why it is not.
-                let result = self.get_with_proof(key, prev_version)?;
+                let (account, proof) = self.get_with_proof(key, prev_version)?;
 
-                pre_state.insert(key.as_fixed_slice().clone(), result);
+                pre_state.insert(key.as_fixed_slice().clone(), account);
+                proofs.push((key.as_fixed_slice().clone(), proof));
                 Ok(())
             })?;
+        //Single proof for all touched keys, so the zkvm does not verify each leaf separately.
+        let pre_state_proof = MultiProof::new(proofs)?;
         // Convert AccountState to Vec<u8> before inserting into the set
-        let serialized_set: HashMap<KeyHash, Option<Vec<u8>>> = set
+        let mut serialized_set: Vec<(KeyHash, Option<Vec<u8>>)> = set
             .into_iter()
             .map(|(key, value)| {
                 let serialized_value = value.map(|account_state| account_state.encode());
                 (KeyHash(key.as_fixed_slice().clone()), serialized_value)
             })
+            .chain(storage.into_iter().map(|(key, value)| {
+                (
+                    KeyHash(key.as_fixed_slice().clone()),
+                    value.map(|i| i.as_slice().to_vec()),
+                )
+            }))
             .collect();
+        //Updates are applied in key order, so the zkvm can replay them in the same order.
+        serialized_set.sort_by(|a, b| a.0 .0.cmp(&b.0 .0));
+
         let tree: JellyfishMerkleTree<MerkleStore, Sha256> =
             JellyfishMerkleTree::new(&self.merkle_store);
 
         // Perform the update with the serialized set
-        match tree.put_value_set(serialized_set, version) {
-            Ok(i) => Ok((
-                i.1,
+        match tree.put_value_set_with_proof(serialized_set, version) {
+            Ok((root, update_proof, tree_update_batch)) => Ok((
+                tree_update_batch,
                 StateUpdate {
                     pre_state,
-                    post_state_root: H256::from(i.0 .0),
+                    pre_state_proof,
+                    post_state_root: H256::from(root.0),
                     pre_state_root,
+                    update_proof,
                 },
             )),
             Err(e) => Err(e),
@@ -107,6 +141,29 @@ impl VmState {
         self.merkle_store.commit()
     }
 
+    //Nodes replaced by an update are indexed, so they can be pruned once no
+    //retained version refers to them.
+    //Stale nodes are only indexed once a retention policy is set, as nothing else reads them.
+    pub fn enable_pruning(&mut self) {
+        self.pruning = true;
+    }
+
+    pub fn pruning_enabled(&self) -> bool {
+        self.pruning
+    }
+
+    pub fn record_stale_nodes<'a>(
+        &self,
+        stale_nodes: impl IntoIterator<Item = &'a StaleNodeIndex>,
+    ) -> Result<(), Error> {
+        self.merkle_store.write_stale_node_index(stale_nodes)
+    }
+
+    //Versions below min_version can no longer be read once pruned.
+    pub fn prune(&self, min_version: Version) -> Result<usize, Error> {
+        self.merkle_store.prune(min_version)
+    }
+
     pub fn get(&self, key: &H256, version: Version) -> Result<Option<AccountState>, Error> {
         let tree: JellyfishMerkleTree<MerkleStore, Sha256> =
             JellyfishMerkleTree::new(&self.merkle_store);
@@ -153,4 +210,111 @@ impl VmState {
 
         Ok((value, proof.1))
     }
+
+    pub fn get_storage_with_proof(
+        &self,
+        key: &H256,
+        version: Version,
+    ) -> Result<(Option<H256>, SparseMerkleProof<Sha256>), Error> {
+        let tree: JellyfishMerkleTree<MerkleStore, Sha256> =
+            JellyfishMerkleTree::new(&self.merkle_store);
+        let root = self.get_root(version)?;
+
+        if root == H256::zero() {
+            return Ok((None, SparseMerkleProof::new(None, vec![])));
+        }
+        let (value, proof) = tree.get_with_proof(KeyHash(key.as_fixed_slice().clone()), version)?;
+
+        let value = match value {
+            Some(i) => match <[u8; 32]>::try_from(i.as_slice()) {
+                Ok(slot) => Some(H256::from(slot)),
+                Err(_) => return Err(anyhow!("Value at key is not a storage slot.")),
+            },
+            None => None,
+        };
+
+        Ok((value, proof))
+    }
+
+    //Leaves after the given key at a version, in key hash order, with a range proof
+    //up to the last leaf returned. None once all leaves are returned.
+    pub fn get_leaves_chunk(
+        &self,
+        version: Version,
+        start_after: Option<KeyHash>,
+        limit: usize,
+    ) -> Result<Option<(Vec<(KeyHash, OwnedValue)>, SparseMerkleRangeProof<Sha256>)>, Error> {
+        let iterator = JellyfishMerkleIterator::new(
+            Arc::new(self.merkle_store.clone()),
+            version,
+            start_after.unwrap_or(KeyHash([0u8; 32])),
+        )?;
+
+        let mut leaves: Vec<(KeyHash, OwnedValue)> = vec![];
+        for leaf in iterator {
+            let (key_hash, value) = leaf?;
+
+            //Iterator starts from the given key inclusively.
+            if Some(key_hash) == start_after {
+                continue;
+            }
+            leaves.push((key_hash, value));
+
+            if leaves.len() >= limit {
+                break;
+            }
+        }
+
+        let last_key = match leaves.last() {
+            Some((key_hash, _)) => *key_hash,
+            None => return Ok(None),
+        };
+        let tree: JellyfishMerkleTree<MerkleStore, Sha256> =
+            JellyfishMerkleTree::new(&self.merkle_store);
+        let proof = tree.get_range_proof(last_key, version)?;
+
+        Ok(Some((leaves, proof)))
+    }
+
+    //Rebuilds an empty state at the given version from chunks of leaves, each
+    //verified against the trusted root before the nodes are written.
+    pub fn restore(
+        &mut self,
+        version: Version,
+        expected_root: H256,
+        chunks: impl IntoIterator<Item = (Vec<(KeyHash, OwnedValue)>, SparseMerkleRangeProof<Sha256>)>,
+    ) -> Result<(), Error> {
+        if self.get_version(true)?.is_some() {
+            return Err(anyhow!("State can only be restored into an empty store."));
+        }
+
+        //Chunks are streamed from the first leaf again, so what an interrupted
+        //restore left behind is cleared instead of resumed.
+        if self.merkle_store.get_rightmost_leaf()?.is_some() {
+            self.merkle_store.clear_tree()?;
+        }
+
+        let mut restore: JellyfishMerkleRestore<Sha256> = JellyfishMerkleRestore::new(
+            Arc::new(self.merkle_store.clone()),
+            version,
+            RootHash(expected_root.as_fixed_slice().clone()),
+        )?;
+
+        for (leaves, proof) in chunks {
+            //Values are only written once the chunk is verified against the root.
+            let values = leaves.clone();
+            restore.add_chunk_impl(leaves, proof)?;
+            self.merkle_store.write_values(version, &values)?;
+        }
+        restore.finish_impl()?;
+
+        if self.get_root(version)? != expected_root {
+            return Err(anyhow!(
+                "Restored state root does not match the expected root."
+            ));
+        }
+
+        self.update_version(version)?;
+        self.merkle_store.commit()
+    }
 }
@@ -1,14 +1,16 @@
 use std::collections::HashMap;
 
-use crate::state::VmState;
+use crate::state::{MultiProof, VmState};
 use crate::stf::StateTransitionFunction;
 use crate::types::{
-    AccountState, AppAccountId, AvailHeader, HeaderStore, StateUpdate, Transaction,
-    TransactionZKVM, TxParams, H256,
+    AccountState, AppAccountId, AvailHeader, HeaderStore, NexusRollupPI, Proof, StateUpdate,
+    Transaction, TransactionZKVM, TxError, TxParams, H256,
 };
-use crate::zkvm::traits::{ZKVMEnv, ZKVMProof};
+use crate::zkvm::traits::{ZKVMEnv, ZKVMProof, ZKVMProver};
+use crate::zkvm::ProverMode;
 use anyhow::{anyhow, Error};
-use jmt::storage::{NodeBatch, TreeUpdateBatch};
+use jmt::proof::UpdateMerkleProof;
+use jmt::storage::TreeUpdateBatch;
 use jmt::Version;
 use serde::Serialize;
 use std::fmt::Debug as DebugTrait;
@@ -23,7 +25,11 @@ pub struct StateMachine<Z: ZKVMEnv, P: ZKVMProof + DebugTrait + Clone> {
     p: PhantomData<P>, //db: NodeDB,
 }
 
-impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z, P> {
+impl<Z, P> StateMachine<Z, P>
+where
+    Z: ZKVMEnv,
+    P: ZKVMProof + Serialize + DebugTrait + Clone + TryFrom<Proof, Error = anyhow::Error>,
+{
     #[instrument(level = "debug", skip(state))]
     pub fn new(state: Arc<Mutex<VmState>>) -> Self {
         debug!("Creating new StateMachine");
@@ -39,7 +45,7 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
     pub async fn commit_state(
         &mut self,
         state_root: &H256,
-        node_batch: &NodeBatch,
+        tree_update: &TreeUpdateBatch,
         batch_number: u32,
     ) -> Result<(), Error> {
         debug!(
@@ -47,7 +53,10 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
             batch_number
         );
         let mut state_lock = self.state.lock().await;
-        state_lock.commit(node_batch)?;
+        state_lock.commit(&tree_update.node_batch)?;
+        if state_lock.pruning_enabled() {
+            state_lock.record_stale_nodes(tree_update.stale_node_index_batch.iter())?;
+        }
 
         let version = match state_lock.get_version(true)? {
             Some(i) => i,
@@ -67,13 +76,24 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
         Ok(())
     }
 
-    #[instrument(level = "debug", skip(self, avail_header, old_nexus_headers, txs), fields(num_txs = txs.len(), num_headers = old_nexus_headers.inner().len()))]
-    pub async fn execute_batch(
+    //Proofs of submit proof txs are verified natively, and added to the prover
+    //for recursion once verified, so the zkvm only sees proofs it can verify.
+    #[instrument(level = "debug", skip(self, avail_header, old_nexus_headers, txs, prover), fields(num_txs = txs.len(), num_headers = old_nexus_headers.inner().len()))]
+    pub async fn execute_batch<R: ZKVMProver<P>>(
         &mut self,
         avail_header: &AvailHeader,
         old_nexus_headers: &HeaderStore,
         txs: &Vec<Transaction>,
-    ) -> Result<(Option<TreeUpdateBatch>, StateUpdate, HashMap<H256, bool>), Error> {
+        prover: &mut R,
+        prover_mode: &ProverMode,
+    ) -> Result<
+        (
+            Option<TreeUpdateBatch>,
+            StateUpdate,
+            HashMap<H256, Result<(), TxError>>,
+        ),
+        Error,
+    > {
         debug!("Executing batch in state machine");
         //TODO: Increment version for each update.
         let mut pre_state: HashMap<[u8; 32], AccountState> = HashMap::new();
@@ -86,18 +106,14 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
             };
 
             txs.iter().try_for_each(|tx| {
-                let app_account_id: AppAccountId = match &tx.params {
-                    TxParams::SubmitProof(submit_proof) => submit_proof.app_id.clone(),
-                    TxParams::InitAccount(init_account) => {
-                        AppAccountId::from(init_account.app_id.clone())
-                    }
-                };
+                let app_account_id: AppAccountId = tx.params.app_id().clone();
 
-                let account_state = match state_lock.get(&app_account_id.as_h256(), prev_version) {
-                    Ok(Some(account)) => account,
-                    Err(e) => return Err(anyhow!("{:?}", e)), // Exit and return the error
-                    Ok(None) => AccountState::zero(),
-                };
+                let account_state =
+                    match state_lock.get(&app_account_id.account_key(), prev_version) {
+                        Ok(Some(account)) => account,
+                        Err(e) => return Err(anyhow!("{:?}", e)), // Exit and return the error
+                        Ok(None) => AccountState::zero(),
+                    };
 
                 pre_state.insert(app_account_id.0.clone(), account_state);
                 Ok(()) // Continue iterating
@@ -120,28 +136,45 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
                 return TransactionZKVM {
                     params: tx.params.clone(),
                     signature: tx.signature.clone(),
+                    nonce: tx.nonce,
                 };
             })
             .collect();
-        let (stf_state_result, tx_result) = self.stf.execute_batch_with_results(
-            avail_header,
-            old_nexus_headers,
-            &zkvm_txs,
-            &pre_state,
-        )?;
+        let (stf_state_result, stf_storage_result, tx_result) =
+            self.stf.execute_batch_with_results(
+                avail_header,
+                old_nexus_headers,
+                &zkvm_txs,
+                &pre_state,
+                |proof, public_inputs| {
+                    let proof = verify_proof_natively::<P>(proof, public_inputs, prover_mode)?;
+                    prover.add_proof_for_recursion(proof)
+                },
+            )?;
         let mut state_lock = self.state.lock().await;
 
         if !stf_state_result.is_empty() {
             let num_state_changes = stf_state_result.len();
 
-            let result = state_lock.update_set(
+            let result = state_lock.update_set_with_storage(
                 stf_state_result
                     .into_iter()
                     .map(|(key, account_state)| {
+                        let key = AppAccountId(key).account_key();
                         if account_state == AccountState::zero() {
+                            (key, None)
+                        } else {
+                            (key, Some(account_state))
+                        }
+                    })
+                    .collect(),
+                stf_storage_result
+                    .into_iter()
+                    .map(|(key, value)| {
+                        if value == H256::zero() {
                             (H256::from(key), None)
                         } else {
-                            (H256::from(key), Some(account_state))
+                            (H256::from(key), Some(value))
                         }
                     })
                     .collect(),
@@ -170,9 +203,39 @@ impl<Z: ZKVMEnv, P: ZKVMProof + Serialize + DebugTrait + Clone> StateMachine<Z,
                     pre_state_root: root,
                     post_state_root: root,
                     pre_state: HashMap::new(),
+                    pre_state_proof: MultiProof::default(),
+                    update_proof: UpdateMerkleProof::new(vec![]),
                 },
                 tx_result,
             ))
         }
     }
 }
+
+//Checks a proof the same way the zkvm does by recursion, against the statement
+//and public inputs expected by the state transition function.
+pub fn verify_proof_natively<P>(
+    proof: &Proof,
+    public_inputs: &NexusRollupPI,
+    prover_mode: &ProverMode,
+) -> Result<P, Error>
+where
+    P: ZKVMProof + TryFrom<Proof, Error = anyhow::Error>,
+{
+    let mut proof = P::try_from(proof.clone())?;
+
+    proof.verify(
+        Some(public_inputs.img_id.as_bytes()),
+        None,
+        prover_mode.clone(),
+    )?;
+
+    let committed: NexusRollupPI = proof.public_inputs()?;
+    if &committed != public_inputs {
+        return Err(anyhow!(
+            "Public inputs of proof do not match the submit proof transaction."
+        ));
+    }
+
+    Ok(proof)
+}
@@ -1,16 +1,18 @@
 use crate::traits::NexusTransaction;
 use crate::{
     types::{
-        AccountState, AppAccountId, AvailHeader, HeaderStore, InitAccount, NexusRollupPI,
-        SubmitProof, TransactionZKVM, TxParams, H256,
+        AccountState, AppAccountId, AvailHeader, CloseAccount, HeaderStore, InitAccount,
+        NexusRollupPI, Proof, ProofKind, RotateStatement, StatementDigest, SubmitProof,
+        TransactionZKVM, TxError, TxParams, UpdateStorage, H256,
     },
-    zkvm::traits::ZKVMEnv,
+    zkvm::{traits::ZKVMEnv, tx_cycle_marker},
 };
-use anyhow::{anyhow, Error};
+use anyhow::anyhow;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 pub struct StateTransitionFunction<Z: ZKVMEnv> {
     z: PhantomData<Z>,
+    cycle_markers: bool,
 }
 
 //Notes
@@ -21,39 +23,68 @@ pub struct StateTransitionFunction<Z: ZKVMEnv> {
 
 impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
     pub fn new() -> Self {
-        StateTransitionFunction { z: PhantomData }
+        StateTransitionFunction {
+            z: PhantomData,
+            cycle_markers: false,
+        }
+    }
+
+    //Marks the cycles of each transaction, only wanted when executing without proving.
+    pub fn with_cycle_markers(mut self, enabled: bool) -> Self {
+        self.cycle_markers = enabled;
+        self
     }
-    pub fn execute_batch_common<F>(
+    //Proofs of submit proof txs are checked with `verify_proof`, which is recursion
+    //inside the zkvm and native verification on the host.
+    pub fn execute_batch_common<F, V>(
         &self,
         new_avail_header: &AvailHeader,
         prev_headers: &HeaderStore,
         txs: &Vec<TransactionZKVM>,
         pre_state: &HashMap<[u8; 32], AccountState>,
         mut on_tx_result: F,
-    ) -> Result<HashMap<[u8; 32], AccountState>, anyhow::Error>
+        mut verify_proof: V,
+    ) -> Result<(HashMap<[u8; 32], AccountState>, HashMap<[u8; 32], H256>), anyhow::Error>
     where
-        F: FnMut(H256, Result<(), anyhow::Error>),
+        F: FnMut(H256, Result<(), TxError>),
+        V: FnMut(&Proof, &NexusRollupPI) -> Result<(), anyhow::Error>,
     {
         if let Some(last_header) = prev_headers.first() {
             if new_avail_header.parent_hash != last_header.avail_header_hash {
                 return Err(anyhow!("Previous Nexus header not valid as per Avail Header for which block is being built. Last header hash: {:?} Current header hash: {:?}, Current header number {}", last_header.avail_header_hash, new_avail_header.parent_hash, new_avail_header.number));
             }
+
+            //Header window is a chain parameter, so headers given by the host have to follow it.
+            if prev_headers.max_size != last_header.header_window as usize
+                || prev_headers.inner().len() > prev_headers.max_size
+            {
+                return Err(anyhow!(
+                    "Header store does not match header window of the chain."
+                ));
+            }
         }
 
         let mut post_state: HashMap<[u8; 32], AccountState> = pre_state.clone();
+        //Storage writes are blind, so storage slots are not part of the pre state.
+        let mut storage: HashMap<[u8; 32], H256> = HashMap::new();
 
-        for tx in txs.iter() {
-            let state_key = match &tx.params {
-                TxParams::SubmitProof(params) => params.app_id.clone(),
-                TxParams::InitAccount(params) => params.app_id.clone(),
-            };
+        for (index, tx) in txs.iter().enumerate() {
+            let state_key = tx.params.app_id().clone();
 
             let pre_state = match post_state.get(&state_key.0) {
                 None => return Err(anyhow!("Incorrect pre state provided by host.")),
                 Some(i) => i,
             };
 
-            let result = self.execute_tx(tx, (&state_key, pre_state), prev_headers);
+            let marker = tx_cycle_marker(index);
+            if self.cycle_markers {
+                Z::cycle_marker_start(&marker);
+            }
+            let result =
+                self.execute_tx(tx, (&state_key, pre_state), prev_headers, &mut verify_proof);
+            if self.cycle_markers {
+                Z::cycle_marker_end(&marker);
+            }
 
             let (app_account_id, account_state) = match result {
                 Ok((i, j)) => {
@@ -67,10 +98,22 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
                 }
             };
 
+            if let TxParams::UpdateStorage(params) = &tx.params {
+                for write in params.writes.iter() {
+                    storage.insert(
+                        app_account_id
+                            .storage_key(account_state.generation, &write.slot)
+                            .as_fixed_slice()
+                            .clone(),
+                        write.value.clone(),
+                    );
+                }
+            }
+
             post_state.insert(app_account_id.0, account_state);
         }
 
-        Ok(post_state)
+        Ok((post_state, storage))
     }
 
     pub fn execute_batch(
@@ -79,56 +122,137 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
         prev_headers: &HeaderStore,
         txs: &Vec<TransactionZKVM>,
         pre_state: &HashMap<[u8; 32], AccountState>,
-    ) -> Result<HashMap<[u8; 32], AccountState>, anyhow::Error> {
-        self.execute_batch_common(new_avail_header, prev_headers, txs, pre_state, |_, _| {})
+    ) -> Result<(HashMap<[u8; 32], AccountState>, HashMap<[u8; 32], H256>), anyhow::Error> {
+        self.execute_batch_common(
+            new_avail_header,
+            prev_headers,
+            txs,
+            pre_state,
+            |_, _| {},
+            Self::verify_in_zkvm,
+        )
     }
 
-    pub fn execute_batch_with_results(
+    pub fn execute_batch_with_results<V>(
         &self,
         new_avail_header: &AvailHeader,
         prev_headers: &HeaderStore,
         txs: &Vec<TransactionZKVM>,
         pre_state: &HashMap<[u8; 32], AccountState>,
-    ) -> Result<(HashMap<[u8; 32], AccountState>, HashMap<H256, bool>), anyhow::Error> {
+        verify_proof: V,
+    ) -> Result<
+        (
+            HashMap<[u8; 32], AccountState>,
+            HashMap<[u8; 32], H256>,
+            HashMap<H256, Result<(), TxError>>,
+        ),
+        anyhow::Error,
+    >
+    where
+        V: FnMut(&Proof, &NexusRollupPI) -> Result<(), anyhow::Error>,
+    {
         let mut tx_results = HashMap::new();
-        let post_state = self.execute_batch_common(
+        let (post_state, storage) = self.execute_batch_common(
             new_avail_header,
             prev_headers,
             txs,
             pre_state,
             |tx_hash, result| {
-                tx_results.insert(tx_hash, result.is_ok());
+                tx_results.insert(tx_hash, result);
             },
+            verify_proof,
         )?;
-        Ok((post_state, tx_results))
+        Ok((post_state, storage, tx_results))
     }
 
-    pub fn execute_tx(
+    //Verifies by recursion, only valid when running inside the zkvm.
+    pub fn verify_in_zkvm(
+        proof: &Proof,
+        public_inputs: &NexusRollupPI,
+    ) -> Result<(), anyhow::Error> {
+        Z::verify_proof(&proof.kind, public_inputs.img_id.0, public_inputs)
+    }
+
+    pub fn execute_tx<V>(
         &self,
         tx: &TransactionZKVM,
         pre_state: (&AppAccountId, &AccountState),
         headers: &HeaderStore,
-    ) -> Result<(AppAccountId, AccountState), anyhow::Error> {
-        //TODO: Signature verification
-        let post_state = match &tx.params {
-            TxParams::SubmitProof(params) => self.submit_proof(params, pre_state, headers)?,
+        verify_proof: &mut V,
+    ) -> Result<(AppAccountId, AccountState), TxError>
+    where
+        V: FnMut(&Proof, &NexusRollupPI) -> Result<(), anyhow::Error>,
+    {
+        self.verify_signature(tx, pre_state)?;
+
+        if tx.nonce != pre_state.1.nonce {
+            return Err(TxError::InvalidNonce {
+                expected: pre_state.1.nonce,
+                got: tx.nonce,
+            });
+        }
+
+        //Checked before the tx is executed, so no check fails after the proof is verified.
+        let next_nonce = match pre_state.1.nonce.checked_add(1) {
+            Some(i) => i,
+            None => return Err(TxError::NonceOverflow),
+        };
+
+        let (app_account_id, mut post_state) = match &tx.params {
+            TxParams::CloseAccount(params) => self.close_account(params, pre_state)?,
+            TxParams::SubmitProof(params) => {
+                self.submit_proof(params, pre_state, headers, verify_proof)?
+            }
             TxParams::InitAccount(params) => self.init_account(params, pre_state)?,
+            TxParams::RotateStatement(params) => {
+                self.rotate_statement(params, pre_state, headers)?
+            }
+            TxParams::UpdateStorage(params) => self.update_storage(params, pre_state)?,
+        };
+
+        post_state.nonce = next_nonce;
+
+        Ok((app_account_id, post_state))
+    }
+
+    fn verify_signature(
+        &self,
+        tx: &TransactionZKVM,
+        pre_state: (&AppAccountId, &AccountState),
+    ) -> Result<(), TxError> {
+        //Init account txs are signed by the owner being registered, all
+        //other txs have to be signed by the owner stored in the account.
+        let signer: &[u8; 32] = match &tx.params {
+            TxParams::InitAccount(params) => &params.owner,
+            _ => {
+                if !pre_state.1.is_initiated() {
+                    return Err(TxError::AccountNotInitiated);
+                }
+
+                &pre_state.1.owner
+            }
         };
 
-        Ok(post_state)
+        tx.signature
+            .verify(tx.nonce, &tx.params, signer)
+            .map_err(|_| TxError::InvalidSignature)
     }
 
-    fn submit_proof(
+    fn submit_proof<V>(
         &self,
         params: &SubmitProof,
         pre_state: (&AppAccountId, &AccountState),
         headers: &HeaderStore,
-    ) -> Result<(AppAccountId, AccountState), Error> {
-        if pre_state.1.clone() == AccountState::zero() {
-            return Err(anyhow!("Invalid transaction, account not initiated."));
+        verify_proof: &mut V,
+    ) -> Result<(AppAccountId, AccountState), TxError>
+    where
+        V: FnMut(&Proof, &NexusRollupPI) -> Result<(), anyhow::Error>,
+    {
+        if !pre_state.1.is_initiated() {
+            return Err(TxError::AccountNotInitiated);
         }
 
-        let public_inputs: NexusRollupPI = NexusRollupPI {
+        let mut public_inputs: NexusRollupPI = NexusRollupPI {
             app_id: params.app_id.clone(),
             nexus_hash: params.nexus_hash.clone(),
             height: params.height,
@@ -136,14 +260,22 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
             state_root: params.state_root.clone(),
             img_id: pre_state.1.statement.clone(),
             rollup_hash: params.data,
+            message_root: params.message_root.clone(),
+            storage_hash: params.storage_hash.clone(),
         };
 
         if public_inputs.app_id != pre_state.0.clone() {
-            return Err(anyhow!("Incorrect app account id"));
+            return Err(TxError::IncorrectAppId);
         }
 
         if public_inputs.start_nexus_hash != H256::from(pre_state.1.start_nexus_hash) {
-            return Err(anyhow!("Not a recursive proof from registered start hash."));
+            return Err(TxError::WrongStartHash);
+        }
+
+        if params.height <= pre_state.1.height {
+            return Err(TxError::StaleRollupHeight {
+                current_height: pre_state.1.height,
+            });
         }
 
         let mut header_hash: H256 = match headers.first() {
@@ -154,7 +286,7 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
 
         for header in headers.inner().iter() {
             if header.hash() != header_hash {
-                return Err(anyhow!("Incorrect header list given by sequencer."));
+                return Err(TxError::IncorrectHeaderList);
             }
 
             if header_hash == public_inputs.nexus_hash {
@@ -167,27 +299,95 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
             continue;
         }
 
-        if found_header_height.is_none() {
-            return Err(anyhow!("Not right fork, or against last 32 blocks"));
+        let found_header_height: u32 = match found_header_height {
+            Some(i) => i,
+            None => {
+                return Err(TxError::UnknownAnchor {
+                    header_window: headers.max_size as u32,
+                })
+            }
+        };
+
+        //Until the first proof, the account has no anchor to compare against.
+        if pre_state.1.height != 0 && found_header_height <= pre_state.1.last_proof_height {
+            return Err(TxError::StaleAnchor {
+                last_proof_height: pre_state.1.last_proof_height,
+            });
         }
 
-        public_inputs.check_consistency(&pre_state.1.statement)?;
+        //Proofs anchored at or after the rotation height have to be against the
+        //pending statement, which then replaces the current one.
+        let rotated = pre_state.1.pending_statement != StatementDigest::zero()
+            && found_header_height >= pre_state.1.pending_statement_height;
+        let (statement, statement_kind) = if rotated {
+            (
+                pre_state.1.pending_statement.clone(),
+                pre_state.1.pending_statement_kind.clone(),
+            )
+        } else {
+            (
+                pre_state.1.statement.clone(),
+                pre_state.1.statement_kind.clone(),
+            )
+        };
 
-        #[cfg(not(feature = "native"))]
-        {
-            match Z::verify(public_inputs.img_id.0, &public_inputs) {
-                Ok(_) => (),
-                Err(e) => return Err(anyhow!("Invalid proof")),
-            }
+        public_inputs.img_id = statement.clone();
+        public_inputs
+            .check_consistency(&statement)
+            .map_err(|_| TxError::StatementMismatch)?;
+
+        if params.proof.kind != statement_kind {
+            return Err(TxError::ProofKindMismatch {
+                expected: statement_kind,
+                got: params.proof.kind.clone(),
+            });
         }
 
+        //Checked natively as well, so the host does not accept proofs the zkvm cannot verify.
+        if !Z::supports_proof_kind(&params.proof.kind) {
+            return Err(TxError::UnsupportedProofKind {
+                kind: params.proof.kind.clone(),
+            });
+        }
+
+        //Has to be the last check, as the host adds the proof for recursion once verified.
+        verify_proof(&params.proof, &public_inputs).map_err(|_| TxError::InvalidProof)?;
+
+        let (pending_statement, pending_statement_height, pending_statement_kind) = if rotated {
+            (StatementDigest::zero(), 0, ProofKind::Risc0Receipt)
+        } else {
+            (
+                pre_state.1.pending_statement.clone(),
+                pre_state.1.pending_statement_height,
+                pre_state.1.pending_statement_kind.clone(),
+            )
+        };
+
         let post_state: AccountState = AccountState {
-            statement: pre_state.1.statement.clone(),
+            statement,
             start_nexus_hash: pre_state.1.start_nexus_hash,
             state_root: params.state_root.as_fixed_slice().clone(),
             height: params.height,
-            //Okay to do unwrap as we check above if it is None.
-            last_proof_height: found_header_height.unwrap(),
+            last_proof_height: found_header_height,
+            owner: pre_state.1.owner,
+            nonce: pre_state.1.nonce,
+            pending_statement,
+            pending_statement_height,
+            statement_kind,
+            pending_statement_kind,
+            message_root: match &params.message_root {
+                Some(i) => i.as_fixed_slice().clone(),
+                None => [0; 32],
+            },
+            rollup_hash: match &params.data {
+                Some(i) => i.as_fixed_slice().clone(),
+                None => [0; 32],
+            },
+            //Writes authorised by an older proof and not yet applied are dropped.
+            storage_hash: match &params.storage_hash {
+                Some(i) => i.as_fixed_slice().clone(),
+                None => [0; 32],
+            },
         };
 
         Ok((public_inputs.app_id.clone(), post_state))
@@ -197,16 +397,118 @@ impl<Z: ZKVMEnv> StateTransitionFunction<Z> {
         &self,
         params: &InitAccount,
         pre_state: (&AppAccountId, &AccountState),
-    ) -> Result<(AppAccountId, AccountState), Error> {
-        if pre_state.1.clone() != AccountState::zero() {
-            return Err(anyhow!("Account already initiated."));
+    ) -> Result<(AppAccountId, AccountState), TxError> {
+        if pre_state.1.is_initiated() {
+            return Err(TxError::AccountAlreadyInitiated);
+        }
+
+        //Accounts would otherwise never be able to submit a proof.
+        if !Z::supports_proof_kind(&params.statement_kind) {
+            return Err(TxError::UnsupportedProofKind {
+                kind: params.statement_kind.clone(),
+            });
         }
 
         let mut post_account = AccountState::zero();
+        post_account.generation = pre_state.1.generation;
+        post_account.nonce = pre_state.1.nonce;
 
         post_account.statement = params.statement.clone();
+        post_account.statement_kind = params.statement_kind.clone();
         post_account.start_nexus_hash = params.start_nexus_hash.as_fixed_slice().clone();
+        post_account.owner = params.owner;
+
+        Ok((pre_state.0.clone(), post_account))
+    }
+
+    fn rotate_statement(
+        &self,
+        params: &RotateStatement,
+        pre_state: (&AppAccountId, &AccountState),
+        headers: &HeaderStore,
+    ) -> Result<(AppAccountId, AccountState), TxError> {
+        if !pre_state.1.is_initiated() {
+            return Err(TxError::AccountNotInitiated);
+        }
+
+        if params.app_id != pre_state.0.clone() {
+            return Err(TxError::IncorrectAppId);
+        }
+
+        if params.statement == StatementDigest::zero() {
+            return Err(TxError::ZeroStatement);
+        }
+
+        if !Z::supports_proof_kind(&params.statement_kind) {
+            return Err(TxError::UnsupportedProofKind {
+                kind: params.statement_kind.clone(),
+            });
+        }
+
+        //Height of the nexus header being built with this transaction.
+        let next_height: u32 = match headers.first() {
+            Some(i) => i.number + 1,
+            None => 0,
+        };
+
+        let effective_height = match params.effective_height {
+            Some(i) if i < next_height => return Err(TxError::PastRotationHeight),
+            Some(i) => i,
+            None => next_height,
+        };
+
+        let mut post_account = pre_state.1.clone();
+
+        //A new rotation replaces any rotation still pending.
+        post_account.pending_statement = params.statement.clone();
+        post_account.pending_statement_kind = params.statement_kind.clone();
+        post_account.pending_statement_height = effective_height;
 
         Ok((pre_state.0.clone(), post_account))
     }
+
+    fn update_storage(
+        &self,
+        params: &UpdateStorage,
+        pre_state: (&AppAccountId, &AccountState),
+    ) -> Result<(AppAccountId, AccountState), TxError> {
+        if !pre_state.1.is_initiated() {
+            return Err(TxError::AccountNotInitiated);
+        }
+
+        if params.app_id != pre_state.0.clone() {
+            return Err(TxError::IncorrectAppId);
+        }
+
+        if pre_state.1.storage_hash == [0; 32]
+            || params.writes_hash().as_fixed_slice() != &pre_state.1.storage_hash
+        {
+            return Err(TxError::UnauthorisedStorageWrite);
+        }
+
+        let mut post_account = pre_state.1.clone();
+
+        //Writes can only be applied once per proof.
+        post_account.storage_hash = [0; 32];
+
+        Ok((pre_state.0.clone(), post_account))
+    }
+
+    fn close_account(
+        &self,
+        params: &CloseAccount,
+        pre_state: (&AppAccountId, &AccountState),
+    ) -> Result<(AppAccountId, AccountState), TxError> {
+        if !pre_state.1.is_initiated() {
+            return Err(TxError::AccountNotInitiated);
+        }
+
+        if params.app_id != pre_state.0.clone() {
+            return Err(TxError::IncorrectAppId);
+        }
+
+        //Storage slots are left in the tree, but are keyed by the generation the
+        //closed leaf moves on from.
+        Ok((pre_state.0.clone(), pre_state.1.closed()))
+    }
 }
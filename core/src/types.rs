@@ -24,10 +24,14 @@ use solabi::{
 };
 //TODO: Implement formatter for H256, to display as hex.
 pub use crate::h256::H256;
-pub use crate::state::types::{AccountState, StatementDigest};
+pub use crate::state::types::{AccountState, ProofKind, StatementDigest};
+use crate::state::MultiProof;
 #[cfg(any(feature = "native"))]
 use crate::zkvm::traits::ZKVMProof;
 use core::fmt::Debug as DebugTrait;
+use ed25519_dalek::{Signature, VerifyingKey};
+#[cfg(any(feature = "native"))]
+use ed25519_dalek::{Signer, SigningKey};
 #[cfg(any(feature = "native"))]
 use utoipa::ToSchema;
 
@@ -42,6 +46,10 @@ pub struct AppId(#[codec(compact)] pub u32);
 #[cfg_attr(feature = "native", derive(ToSchema))]
 pub struct TxSignature(#[serde(with = "BigArray")] pub [u8; 64]);
 
+//Ed25519 secret key used by adapters to sign transactions sent to nexus.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TxSigningKey(pub [u8; 32]);
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[cfg_attr(feature = "native", derive(ToSchema))]
 pub struct AccountWithProof {
@@ -53,6 +61,23 @@ pub struct AccountWithProof {
     pub proof_hex: Vec<String>,
     pub value_hash_hex: String,
     pub nexus_state_root_hex: String,
+    pub status: AccountStatus,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub enum AccountStatus {
+    Active,
+    Closed,
+    NotFound,
+}
+
+//Stored in node db when an account is closed, to point to the closing tx.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClosedAccount {
+    pub tx_hash: H256,
+    pub block_hash: H256,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
@@ -67,6 +92,9 @@ pub struct UpdatedBlob {
 pub enum TxParams {
     SubmitProof(SubmitProof),
     InitAccount(InitAccount),
+    RotateStatement(RotateStatement),
+    CloseAccount(CloseAccount),
+    UpdateStorage(UpdateStorage),
 }
 
 #[cfg(any(feature = "native"))]
@@ -74,12 +102,14 @@ pub enum TxParams {
 #[cfg_attr(feature = "native", derive(ToSchema))]
 pub struct Transaction {
     pub signature: TxSignature,
+    pub nonce: u64,
     pub params: TxParams,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Encode, Decode)]
 pub struct TransactionZKVM {
     pub signature: TxSignature,
+    pub nonce: u64,
     pub params: TxParams,
 }
 
@@ -92,18 +122,65 @@ pub struct SubmitProof {
     pub height: u32,
     pub app_id: AppAccountId,
     pub data: Option<H256>,
+    //Root of messages exported by the rollup, as part of this proof.
+    pub message_root: Option<H256>,
+    //Hash of storage writes the proof authorises, applied with an update storage transaction.
+    pub storage_hash: Option<H256>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Encode, Decode, PartialEq, Eq)]
 #[cfg_attr(feature = "native", derive(ToSchema))]
-pub struct Proof(pub Vec<u8>);
+pub struct Proof {
+    pub kind: ProofKind,
+    pub data: Vec<u8>,
+}
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
 #[cfg_attr(feature = "native", derive(ToSchema))]
 pub struct InitAccount {
     pub app_id: AppAccountId,
     pub statement: StatementDigest,
+    pub statement_kind: ProofKind,
     pub start_nexus_hash: H256,
+    pub owner: [u8; 32],
+}
+
+//Schedules a change of the statement an account's proofs are verified against.
+//Proofs anchored to a nexus header at or after `effective_height` have to use
+//the new statement, older proofs still verify against the current one.
+//The statement kind can change too, to move an account to another zkvm.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct RotateStatement {
+    pub app_id: AppAccountId,
+    pub statement: StatementDigest,
+    pub statement_kind: ProofKind,
+    pub effective_height: Option<u32>,
+}
+
+//Clears the account, leaving a closed leaf in the state tree. The app account
+//id can be registered again with a new init account transaction.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct CloseAccount {
+    pub app_id: AppAccountId,
+}
+
+//Writes slots of the account's storage. The writes have to match the storage
+//hash committed to by the latest proof of the account.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct UpdateStorage {
+    pub app_id: AppAccountId,
+    pub writes: Vec<StorageWrite>,
+}
+
+//Zero value removes the slot from the state tree.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct StorageWrite {
+    pub slot: H256,
+    pub value: H256,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -115,6 +192,8 @@ pub struct NexusRollupPI {
     pub app_id: AppAccountId,
     pub img_id: StatementDigest,
     pub rollup_hash: Option<H256>,
+    pub message_root: Option<H256>,
+    pub storage_hash: Option<H256>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
@@ -124,14 +203,65 @@ pub struct NexusHeader {
     pub prev_state_root: H256,
     pub state_root: H256,
     pub tx_root: H256,
+    pub receipts_root: H256,
+    //Root over message roots exported by accounts in this block.
+    pub message_root: H256,
     pub avail_header_hash: H256,
     pub number: u32,
+    pub header_window: u32,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
 pub struct TransactionResult {
     pub hash: H256,
     pub result: bool,
+    pub error: Option<TxError>,
+}
+
+//Reason a transaction was rejected by the state transition function.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub enum TxError {
+    AccountNotInitiated,
+    AccountAlreadyInitiated,
+    InvalidSignature,
+    InvalidNonce { expected: u64, got: u64 },
+    NonceOverflow,
+    IncorrectAppId,
+    WrongStartHash,
+    StaleRollupHeight { current_height: u32 },
+    StaleAnchor { last_proof_height: u32 },
+    UnknownAnchor { header_window: u32 },
+    IncorrectHeaderList,
+    StatementMismatch,
+    InvalidProof,
+    ZeroStatement,
+    PastRotationHeight,
+    UnauthorisedStorageWrite,
+    ProofKindMismatch { expected: ProofKind, got: ProofKind },
+    UnsupportedProofKind { kind: ProofKind },
+}
+
+//Outcome of a transaction, committed to in the receipts root of the nexus header.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Encode, Decode)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct TxReceipt {
+    pub tx_hash: H256,
+    pub success: bool,
+    //Zero for successful transactions.
+    pub error_code: u32,
+}
+
+//Proof that a receipt is part of the receipts root of a nexus block.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TxReceiptProof {
+    pub receipt: TxReceipt,
+    pub block_hash: H256,
+    pub receipts_root: H256,
+    pub index: u32,
+    pub leaf_count: u32,
+    pub proof: Vec<H256>,
 }
 
 #[cfg(any(feature = "native"))]
@@ -156,6 +286,71 @@ pub struct TransactionWithStatus {
     pub transaction: Transaction,
     pub status: TransactionStatus,
     pub block_hash: Option<H256>,
+    //Set for failed transactions.
+    pub error: Option<TxError>,
+}
+
+//Value of a storage slot of an account, with a proof against the state root.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct StorageWithProof {
+    pub app_id: AppAccountId,
+    pub slot: H256,
+    pub key: H256,
+    //Zero if the slot is not set.
+    pub value: H256,
+    pub proof: Vec<H256>,
+    pub state_root: H256,
+    pub nexus_header: NexusHeader,
+}
+
+//Proof that a message root exported by an account is part of the message root
+//of a nexus block. Messages are proven against the account's message root by the rollup.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct MessageRootProof {
+    pub app_id: AppAccountId,
+    pub account_message_root: H256,
+    pub block_hash: H256,
+    pub message_root: H256,
+    pub index: u32,
+    pub leaf_count: u32,
+    pub proof: Vec<H256>,
+}
+
+//Starting point of the chain, loaded when a node boots with an empty database.
+//Nodes using the same genesis build the same genesis header and state root.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GenesisConfig {
+    pub avail_start_height: u32,
+    //Fetched from Avail at first boot if not set.
+    pub avail_start_hash: Option<H256>,
+    //Number of latest nexus headers proofs can be anchored to.
+    pub header_window: u32,
+    pub accounts: Vec<GenesisAccount>,
+}
+
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GenesisAccount {
+    pub app_id: AppAccountId,
+    pub statement: StatementDigest,
+    pub statement_kind: ProofKind,
+    pub start_nexus_hash: H256,
+    pub owner: [u8; 32],
+}
+
+//Proof that a transaction is part of the tx root of a nexus block.
+#[cfg(any(feature = "native"))]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TxInclusionProof {
+    pub tx_hash: H256,
+    pub block_hash: H256,
+    pub tx_root: H256,
+    pub index: u32,
+    pub leaf_count: u32,
+    pub proof: Vec<H256>,
 }
 
 #[cfg(any(feature = "native"))]
@@ -176,9 +371,25 @@ pub struct NexusBlockWithPointers {
 pub struct StateUpdate {
     pub pre_state_root: H256,
     pub post_state_root: H256,
-    pub pre_state: HashMap<[u8; 32], (Option<AccountState>, SparseMerkleProof<Sha256>)>,
+    pub pre_state: HashMap<[u8; 32], Option<AccountState>>,
+    pub pre_state_proof: MultiProof,
+    //Proof of the updates applied to the tree in ascending key order, so the
+    //post state root can be recomputed from the pre state root.
+    pub update_proof: UpdateMerkleProof<Sha256>,
 }
 
+//Proofs can be anchored to any nexus header from `oldest_height` to `latest_height`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "native", derive(ToSchema))]
+pub struct HeaderWindow {
+    pub header_window: u32,
+    pub latest_height: u32,
+    pub oldest_height: u32,
+}
+
+//Used when the node is started without a genesis.
+pub const DEFAULT_HEADER_WINDOW: u32 = 32;
+
 //TODO: Store on hash list, instead of headers.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HeaderStore {
@@ -451,6 +662,28 @@ impl AppAccountId {
     pub fn as_h256(&self) -> H256 {
         H256::from(self.0)
     }
+
+    //Key of the account in the state tree. App account ids can be any value, so
+    //they are hashed with a prefix to keep them apart from storage keys.
+    pub fn account_key(&self) -> H256 {
+        let mut hasher = ShaHasher::new();
+        hasher.0.update(b"account");
+        hasher.write_h256(&self.as_h256());
+
+        hasher.finish()
+    }
+
+    //Key of a storage slot of the account in the state tree, for the generation
+    //of the account the slot was written in.
+    pub fn storage_key(&self, generation: u64, slot: &H256) -> H256 {
+        let mut hasher = ShaHasher::new();
+        hasher.0.update(b"storage");
+        hasher.write_h256(&self.as_h256());
+        hasher.0.update(&generation.to_be_bytes());
+        hasher.write_h256(slot);
+
+        hasher.finish()
+    }
 }
 
 impl NexusRollupPI {
@@ -468,7 +701,15 @@ impl TryFrom<risc0_zkvm::Receipt> for Proof {
     type Error = Error;
 
     fn try_from(value: risc0_zkvm::Receipt) -> Result<Self, Self::Error> {
-        Ok(Self(to_vec(&value)?))
+        //Groth16 receipts can not be verified by recursion.
+        if let risc0_zkvm::InnerReceipt::Groth16(_) = &value.inner {
+            return Err(serde::de::Error::custom("Groth16 receipts can not be submitted."));
+        }
+
+        Ok(Self {
+            kind: ProofKind::Risc0Receipt,
+            data: to_vec(&value)?,
+        })
     }
 }
 
@@ -477,13 +718,107 @@ impl TryInto<risc0_zkvm::Receipt> for Proof {
     type Error = Error;
 
     fn try_into(self) -> Result<risc0_zkvm::Receipt, Self::Error> {
-        Ok(from_slice(&self.0)?)
+        match self.kind {
+            ProofKind::Risc0Receipt => Ok(from_slice(&self.data)?),
+            _ => Err(serde::de::Error::custom("Not a risc0 proof.")),
+        }
+    }
+}
+
+impl TxParams {
+    pub fn app_id(&self) -> &AppAccountId {
+        match self {
+            TxParams::SubmitProof(params) => &params.app_id,
+            TxParams::InitAccount(params) => &params.app_id,
+            TxParams::RotateStatement(params) => &params.app_id,
+            TxParams::CloseAccount(params) => &params.app_id,
+            TxParams::UpdateStorage(params) => &params.app_id,
+        }
+    }
+
+    //Payload that is signed and hashed for a transaction. Includes the nonce
+    //so the same params can be submitted again with a new nonce.
+    pub fn signing_payload(&self, nonce: u64) -> Vec<u8> {
+        (nonce, self).encode()
+    }
+}
+
+impl SubmitProof {
+    //Leaf of the block message tree, if the proof exports any messages.
+    pub fn message_leaf(&self) -> Option<H256> {
+        match &self.message_root {
+            Some(message_root) if message_root != &H256::zero() => {
+                let mut hasher = ShaHasher::new();
+                hasher.write_h256(&self.app_id.as_h256());
+                hasher.write_h256(message_root);
+
+                Some(hasher.finish())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl UpdateStorage {
+    //Committed to as the storage hash of a proof.
+    pub fn writes_hash(&self) -> H256 {
+        let mut hasher = ShaHasher::new();
+        hasher.0.update(&self.writes.encode());
+
+        hasher.finish()
+    }
+}
+
+impl TxSignature {
+    pub fn verify(
+        &self,
+        nonce: u64,
+        params: &TxParams,
+        public_key: &[u8; 32],
+    ) -> Result<(), anyhow::Error> {
+        let verifying_key = match VerifyingKey::from_bytes(public_key) {
+            Ok(i) => i,
+            Err(_) => return Err(anyhow::anyhow!("Invalid signer public key.")),
+        };
+
+        verifying_key
+            .verify_strict(
+                &params.signing_payload(nonce),
+                &Signature::from_bytes(&self.0),
+            )
+            .map_err(|_| anyhow::anyhow!("Invalid transaction signature."))
+    }
+}
+
+#[cfg(any(feature = "native"))]
+impl TxSigningKey {
+    pub fn public_key(&self) -> [u8; 32] {
+        SigningKey::from_bytes(&self.0).verifying_key().to_bytes()
+    }
+
+    pub fn sign(&self, nonce: u64, params: &TxParams) -> TxSignature {
+        TxSignature(
+            SigningKey::from_bytes(&self.0)
+                .sign(&params.signing_payload(nonce))
+                .to_bytes(),
+        )
+    }
+}
+
+#[cfg(any(feature = "native"))]
+impl Transaction {
+    pub fn new_signed(params: TxParams, nonce: u64, signing_key: &TxSigningKey) -> Self {
+        Self {
+            signature: signing_key.sign(nonce, &params),
+            nonce,
+            params,
+        }
     }
 }
 
 impl NexusTransaction for TransactionZKVM {
     fn hash(&self) -> H256 {
-        let serialized = self.params.encode();
+        let serialized = self.params.signing_payload(self.nonce);
         let mut hasher = ShaHasher::new();
 
         hasher.0.update(&serialized);
@@ -494,7 +829,7 @@ impl NexusTransaction for TransactionZKVM {
 #[cfg(any(feature = "native"))]
 impl NexusTransaction for Transaction {
     fn hash(&self) -> H256 {
-        let serialized = self.params.encode();
+        let serialized = self.params.signing_payload(self.nonce);
         let mut hasher = ShaHasher::new();
 
         hasher.0.update(&serialized);
@@ -502,6 +837,109 @@ impl NexusTransaction for Transaction {
     }
 }
 
+impl TxError {
+    //Codes are committed to in receipts, so existing codes must not change.
+    pub fn code(&self) -> u32 {
+        match self {
+            TxError::AccountNotInitiated => 1,
+            TxError::AccountAlreadyInitiated => 2,
+            TxError::InvalidSignature => 3,
+            TxError::InvalidNonce { .. } => 4,
+            TxError::NonceOverflow => 5,
+            TxError::IncorrectAppId => 6,
+            TxError::WrongStartHash => 7,
+            TxError::StaleRollupHeight { .. } => 8,
+            TxError::StaleAnchor { .. } => 9,
+            TxError::UnknownAnchor { .. } => 10,
+            TxError::IncorrectHeaderList => 11,
+            TxError::StatementMismatch => 12,
+            TxError::InvalidProof => 13,
+            TxError::ZeroStatement => 14,
+            TxError::PastRotationHeight => 15,
+            TxError::UnauthorisedStorageWrite => 16,
+            TxError::ProofKindMismatch { .. } => 17,
+            TxError::UnsupportedProofKind { .. } => 18,
+        }
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::AccountNotInitiated => {
+                write!(f, "Invalid transaction, account not initiated.")
+            }
+            TxError::AccountAlreadyInitiated => write!(f, "Account already initiated."),
+            TxError::InvalidSignature => write!(f, "Invalid transaction signature."),
+            TxError::InvalidNonce { expected, got } => {
+                write!(f, "Invalid nonce. Expected {}, got {}.", expected, got)
+            }
+            TxError::NonceOverflow => write!(f, "Nonce overflow for account."),
+            TxError::IncorrectAppId => write!(f, "Incorrect app account id"),
+            TxError::WrongStartHash => {
+                write!(f, "Not a recursive proof from registered start hash.")
+            }
+            TxError::StaleRollupHeight { current_height } => write!(
+                f,
+                "Stale rollup height, proof has to be for a height greater than {}.",
+                current_height
+            ),
+            TxError::StaleAnchor { last_proof_height } => write!(
+                f,
+                "Stale nexus anchor, proof has to be against a block after {}.",
+                last_proof_height
+            ),
+            TxError::UnknownAnchor { header_window } => write!(
+                f,
+                "Not right fork, or against last {} blocks",
+                header_window
+            ),
+            TxError::IncorrectHeaderList => write!(f, "Incorrect header list given by sequencer."),
+            TxError::StatementMismatch => write!(f, "The same img_id not used for recursion"),
+            TxError::InvalidProof => write!(f, "Invalid proof"),
+            TxError::ZeroStatement => write!(f, "Statement digest cannot be zero."),
+            TxError::PastRotationHeight => write!(
+                f,
+                "Statement rotation cannot be effective from a past nexus height."
+            ),
+            TxError::UnauthorisedStorageWrite => write!(
+                f,
+                "Storage writes do not match storage hash of the latest proof."
+            ),
+            TxError::ProofKindMismatch { expected, got } => write!(
+                f,
+                "Proof kind does not match statement. Expected {:?}, got {:?}.",
+                expected, got
+            ),
+            TxError::UnsupportedProofKind { kind } => {
+                write!(f, "Proofs of kind {:?} cannot be verified by nexus.", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+impl TxReceipt {
+    pub fn new(tx_hash: H256, error: Option<&TxError>) -> Self {
+        Self {
+            tx_hash,
+            success: error.is_none(),
+            error_code: match error {
+                Some(e) => e.code(),
+                None => 0,
+            },
+        }
+    }
+
+    pub fn hash(&self) -> H256 {
+        let mut hasher = ShaHasher::new();
+        hasher.0.update(self.encode());
+
+        hasher.finish()
+    }
+}
+
 impl NexusHeader {
     pub fn hash(&self) -> H256 {
         let serialized = self.encode();
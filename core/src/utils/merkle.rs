@@ -0,0 +1,238 @@
+use crate::types::H256;
+use crate::utils::hasher::ShaHasher;
+use anyhow::{anyhow, Error};
+
+//Binary merkle tree over hashes. Leaves and internal nodes are domain separated,
+//and a node without a pair is moved up a level as is, instead of being duplicated.
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn hash_leaf(leaf: &H256) -> H256 {
+    let mut hasher = ShaHasher::new();
+    hasher.write_byte(LEAF_PREFIX);
+    hasher.write_h256(leaf);
+
+    hasher.finish()
+}
+
+fn hash_node(left: &H256, right: &H256) -> H256 {
+    let mut hasher = ShaHasher::new();
+    hasher.write_byte(NODE_PREFIX);
+    hasher.write_h256(left);
+    hasher.write_h256(right);
+
+    hasher.finish()
+}
+
+fn next_level(level: &[H256]) -> Vec<H256> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => single.clone(),
+            _ => unreachable!("Chunks are of at most two nodes."),
+        })
+        .collect()
+}
+
+//Root is zero if there are no leaves.
+pub fn merkle_root(leaves: &[H256]) -> H256 {
+    if leaves.is_empty() {
+        return H256::zero();
+    }
+
+    let mut level: Vec<H256> = leaves.iter().map(hash_leaf).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+
+    level[0].clone()
+}
+
+//Siblings from the leaf up to the root, levels where the node has no pair are skipped.
+pub fn merkle_proof(leaves: &[H256], index: usize) -> Result<Vec<H256>, Error> {
+    if index >= leaves.len() {
+        return Err(anyhow!("Leaf index out of range."));
+    }
+
+    let mut proof: Vec<H256> = vec![];
+    let mut level: Vec<H256> = leaves.iter().map(hash_leaf).collect();
+    let mut index = index;
+
+    while level.len() > 1 {
+        let sibling_index = index ^ 1;
+        if sibling_index < level.len() {
+            proof.push(level[sibling_index].clone());
+        }
+
+        level = next_level(&level);
+        index /= 2;
+    }
+
+    Ok(proof)
+}
+
+pub fn verify_merkle_proof(
+    leaf: &H256,
+    index: usize,
+    leaf_count: usize,
+    proof: &[H256],
+    root: &H256,
+) -> Result<(), Error> {
+    if index >= leaf_count {
+        return Err(anyhow!("Leaf index out of range."));
+    }
+
+    let mut siblings = proof.iter();
+    let mut hash = hash_leaf(leaf);
+    let mut index = index;
+    let mut level_len = leaf_count;
+
+    while level_len > 1 {
+        let sibling_index = index ^ 1;
+        if sibling_index < level_len {
+            let sibling = match siblings.next() {
+                Some(i) => i,
+                None => return Err(anyhow!("Merkle proof too short.")),
+            };
+
+            hash = if index % 2 == 0 {
+                hash_node(&hash, sibling)
+            } else {
+                hash_node(sibling, &hash)
+            };
+        }
+
+        level_len = (level_len + 1) / 2;
+        index /= 2;
+    }
+
+    if siblings.next().is_some() {
+        return Err(anyhow!("Merkle proof too long."));
+    }
+
+    if &hash != root {
+        return Err(anyhow!("Merkle root does not match."));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TxError, TxReceipt};
+
+    fn h256(hex_str: &str) -> H256 {
+        let bytes: [u8; 32] = hex::decode(hex_str).unwrap().try_into().unwrap();
+        H256::from(bytes)
+    }
+
+    fn leaves(count: u8) -> Vec<H256> {
+        (1..=count).map(|i| H256::from([i; 32])).collect()
+    }
+
+    #[test]
+    fn test_leaf_and_node_prefixes() {
+        //sha256(0x00 || leaf)
+        assert_eq!(
+            merkle_root(&leaves(1)),
+            h256("dcffe786ded16d283c663846ad0c4ff26558fccde36ca9d30b2ea19eade9fc0e")
+        );
+        //sha256(0x01 || left || right)
+        assert_eq!(
+            merkle_root(&leaves(2)),
+            h256("3a066e0f40c6a1981ebfa60d2411625d0517ae22c2fc8c7c1784ff8a75c78565")
+        );
+        assert_eq!(merkle_root(&[]), H256::zero());
+    }
+
+    #[test]
+    fn test_odd_node_promotion() {
+        let three = leaves(3);
+        let root = merkle_root(&three);
+
+        assert_eq!(
+            root,
+            h256("df896896c799531f1fd1e556cea26a6989ab06853bcbfdd3e4f5097a611f658f")
+        );
+        assert_eq!(
+            root,
+            hash_node(&merkle_root(&three[..2]), &hash_leaf(&three[2]))
+        );
+        //The last leaf is not duplicated.
+        let mut duplicated = three.clone();
+        duplicated.push(three[2].clone());
+        assert_ne!(root, merkle_root(&duplicated));
+        //Promoted nodes have no sibling at their level.
+        assert_eq!(merkle_proof(&three, 2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merkle_proof_round_trip() {
+        for count in 1..=9u8 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves);
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, index).unwrap();
+                verify_merkle_proof(leaf, index, leaves.len(), &proof, &root).unwrap();
+
+                let wrong_leaf = H256::from([0xff; 32]);
+                assert!(
+                    verify_merkle_proof(&wrong_leaf, index, leaves.len(), &proof, &root).is_err()
+                );
+                if !proof.is_empty() {
+                    assert!(
+                        verify_merkle_proof(leaf, index, leaves.len(), &proof[1..], &root).is_err()
+                    );
+                }
+                let mut long_proof = proof.clone();
+                long_proof.push(H256::zero());
+                assert!(
+                    verify_merkle_proof(leaf, index, leaves.len(), &long_proof, &root).is_err()
+                );
+            }
+
+            assert!(merkle_proof(&leaves, leaves.len()).is_err());
+            assert!(
+                verify_merkle_proof(&leaves[0], leaves.len(), leaves.len(), &[], &root).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_receipts_root() {
+        let receipts = vec![
+            TxReceipt::new(H256::from([1u8; 32]), None),
+            TxReceipt::new(H256::from([2u8; 32]), Some(&TxError::InvalidProof)),
+        ];
+        let receipt_hashes: Vec<H256> = receipts.iter().map(|receipt| receipt.hash()).collect();
+
+        //sha256 of the scale encoded receipt.
+        assert_eq!(
+            receipt_hashes,
+            vec![
+                h256("45601d6abd4b5f481f78360d97b20177c906650a81fed71da241278207d55a98"),
+                h256("39296ca52486c046dfee0a50c1bc1e04b0accd28f4d28174567335df04b7eb3a"),
+            ]
+        );
+
+        let receipts_root = merkle_root(&receipt_hashes);
+        assert_eq!(
+            receipts_root,
+            h256("77f47857ffc0d8f874edd2375cb6b7ff39926962e85ca030d62ce5e9eccbad71")
+        );
+        for (index, receipt_hash) in receipt_hashes.iter().enumerate() {
+            let proof = merkle_proof(&receipt_hashes, index).unwrap();
+            verify_merkle_proof(
+                receipt_hash,
+                index,
+                receipt_hashes.len(),
+                &proof,
+                &receipts_root,
+            )
+            .unwrap();
+        }
+    }
+}
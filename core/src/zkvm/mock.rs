@@ -0,0 +1,218 @@
+use super::traits::{ZKVMEnv, ZKVMProof, ZKVMProver};
+use super::{ExecutionStats, ProverMode};
+use crate::prover::run;
+use crate::types::{Proof, ProofKind};
+use crate::utils::hasher::{Digest, Sha256};
+use anyhow::anyhow;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+//The guest is executed on the proving thread, so its io is kept per thread.
+thread_local! {
+    static INPUTS: RefCell<VecDeque<Vec<u8>>> = RefCell::new(VecDeque::new());
+    static ASSUMPTIONS: RefCell<Vec<MockProof>> = RefCell::new(vec![]);
+    static JOURNAL: RefCell<Vec<u8>> = RefCell::new(vec![]);
+    static MARKERS: RefCell<Vec<String>> = RefCell::new(vec![]);
+}
+
+//Mock programs are identified by the hash of the elf, as nothing is executed from it.
+pub fn image_id(elf: &[u8]) -> [u32; 8] {
+    let digest = Sha256::digest(elf);
+    let mut id = [0u32; 8];
+    for (word, chunk) in id.iter_mut().zip(digest.chunks(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    id
+}
+
+pub struct MockProver {
+    inputs: Vec<Vec<u8>>,
+    assumptions: Vec<MockProof>,
+    image_id: [u32; 8],
+    guest: fn(),
+    prover_mode: ProverMode,
+    execution_stats: Option<ExecutionStats>,
+}
+
+impl MockProver {
+    //Runs the given guest instead of the nexus runtime, for adapters.
+    pub fn with_guest(mut self, guest: fn()) -> Self {
+        self.guest = guest;
+        self
+    }
+
+    pub fn image_id(&self) -> [u32; 8] {
+        self.image_id
+    }
+}
+
+impl ZKVMProver<MockProof> for MockProver {
+    fn new(elf: Vec<u8>, prover_mode: ProverMode) -> Self {
+        Self {
+            inputs: vec![],
+            assumptions: vec![],
+            image_id: image_id(&elf),
+            guest: run::<MockZKVM>,
+            prover_mode,
+            execution_stats: None,
+        }
+    }
+
+    fn add_input<T: Serialize>(&mut self, input: &T) -> Result<(), anyhow::Error> {
+        self.inputs.push(bincode::serialize(input)?);
+        Ok(())
+    }
+
+    fn add_proof_for_recursion(&mut self, proof: MockProof) -> Result<(), anyhow::Error> {
+        self.assumptions.push(proof);
+        Ok(())
+    }
+
+    fn prove(&mut self) -> Result<MockProof, anyhow::Error> {
+        INPUTS.with(|inputs| *inputs.borrow_mut() = self.inputs.iter().cloned().collect());
+        ASSUMPTIONS.with(|assumptions| *assumptions.borrow_mut() = self.assumptions.clone());
+        JOURNAL.with(|journal| journal.borrow_mut().clear());
+        MARKERS.with(|markers| markers.borrow_mut().clear());
+
+        let result = std::panic::catch_unwind(self.guest);
+
+        INPUTS.with(|inputs| inputs.borrow_mut().clear());
+        ASSUMPTIONS.with(|assumptions| assumptions.borrow_mut().clear());
+        let public_values = JOURNAL.with(|journal| std::mem::take(&mut *journal.borrow_mut()));
+        let markers = MARKERS.with(|markers| std::mem::take(&mut *markers.borrow_mut()));
+
+        if result.is_err() {
+            return Err(anyhow!("Guest panicked during native execution."));
+        }
+
+        //Native execution has no cycles, so only the markers hit are reported.
+        if self.prover_mode == ProverMode::ExecuteOnly {
+            self.execution_stats = Some(
+                ExecutionStats::default()
+                    .with_tx_markers(markers.into_iter().map(|label| (label, 0))),
+            );
+        }
+
+        Ok(MockProof {
+            image_id: self.image_id,
+            public_values,
+        })
+    }
+
+    fn execution_stats(&self) -> Option<ExecutionStats> {
+        self.execution_stats.clone()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockProof {
+    pub image_id: [u32; 8],
+    pub public_values: Vec<u8>,
+}
+
+impl ZKVMProof for MockProof {
+    fn public_inputs<V: Serialize + DeserializeOwned + Clone>(
+        &mut self,
+    ) -> Result<V, anyhow::Error> {
+        bincode::deserialize(&self.public_values).map_err(|e| anyhow!(e))
+    }
+
+    fn verify(
+        &self,
+        img_id: Option<[u8; 32]>,
+        elf: Option<Vec<u8>>,
+        _: ProverMode,
+    ) -> Result<(), anyhow::Error> {
+        let expected = match (img_id, elf) {
+            (Some(id), _) => {
+                let mut words = [0u32; 8];
+                for (word, chunk) in words.iter_mut().zip(id.chunks(4)) {
+                    *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                }
+                words
+            }
+            (None, Some(elf)) => image_id(&elf),
+            (None, None) => return Err(anyhow!("Image id or ELF is required")),
+        };
+
+        if expected != self.image_id {
+            return Err(anyhow!("Mock proof is not for the given program."));
+        }
+
+        Ok(())
+    }
+
+    fn compress(&mut self) -> Result<MockProof, anyhow::Error> {
+        Ok(self.clone())
+    }
+}
+
+impl TryInto<Proof> for MockProof {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> Result<Proof, Self::Error> {
+        Ok(Proof {
+            kind: ProofKind::Mock,
+            data: bincode::serialize(&self)?,
+        })
+    }
+}
+
+impl TryFrom<Proof> for MockProof {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Proof) -> Result<Self, Self::Error> {
+        if value.kind != ProofKind::Mock {
+            return Err(anyhow!("Not a mock proof, got {:?}.", value.kind));
+        }
+
+        Ok(bincode::deserialize(&value.data)?)
+    }
+}
+
+pub struct MockZKVM();
+
+impl ZKVMEnv for MockZKVM {
+    fn read_input<T: DeserializeOwned>() -> Result<T, anyhow::Error> {
+        let input = match INPUTS.with(|inputs| inputs.borrow_mut().pop_front()) {
+            Some(i) => i,
+            None => return Err(anyhow!("No input left to read.")),
+        };
+
+        bincode::deserialize(&input).map_err(|e| anyhow!(e))
+    }
+
+    //Assumptions stand in for recursion, a proof with the same public values must be added.
+    fn verify<T: Serialize>(img_id: [u32; 8], public_inputs: &T) -> Result<(), anyhow::Error> {
+        let public_values = bincode::serialize(public_inputs)?;
+        let found = ASSUMPTIONS.with(|assumptions| {
+            assumptions
+                .borrow()
+                .iter()
+                .any(|proof| proof.image_id == img_id && proof.public_values == public_values)
+        });
+
+        if found {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "No proof added for the image id and public inputs."
+            ))
+        }
+    }
+
+    fn commit<T: Serialize>(data: &T) {
+        let serialized = bincode::serialize(data).expect("Could not encode public values");
+        JOURNAL.with(|journal| journal.borrow_mut().extend(serialized));
+    }
+
+    fn cycle_marker_end(label: &str) {
+        MARKERS.with(|markers| markers.borrow_mut().push(label.to_string()));
+    }
+
+    fn proof_kind() -> ProofKind {
+        ProofKind::Mock
+    }
+}
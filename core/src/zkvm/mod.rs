@@ -8,10 +8,53 @@ pub mod risczero;
 #[cfg(any(feature = "native-sp1", feature = "zkvm-sp1"))]
 pub mod sp1;
 
+#[cfg(any(feature = "native-mock"))]
+pub mod mock;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ProverMode {
     Compressed,
     Groth16,
+    //Only supported by sp1.
+    Plonk,
     NoAggregation,
     MockProof,
+    //Executes the guest without proving, to report execution stats.
+    ExecuteOnly,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutionStats {
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+    pub segments: u64,
+    //Cycles of each transaction, in batch order.
+    pub tx_cycles: Vec<u64>,
+}
+
+//Label of the cycle marker around a transaction in the guest.
+pub fn tx_cycle_marker(index: usize) -> String {
+    format!("tx-{}", index)
+}
+
+impl ExecutionStats {
+    //Collects transaction cycles from the cycle markers reported by the zkvm.
+    pub fn with_tx_markers(mut self, markers: impl IntoIterator<Item = (String, u64)>) -> Self {
+        for (label, cycles) in markers {
+            let index = match label
+                .strip_prefix("tx-")
+                .and_then(|i| i.parse::<usize>().ok())
+            {
+                Some(i) => i,
+                None => continue,
+            };
+
+            if self.tx_cycles.len() <= index {
+                self.tx_cycles.resize(index + 1, 0);
+            }
+            self.tx_cycles[index] += cycles;
+        }
+
+        self
+    }
 }
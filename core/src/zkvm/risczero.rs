@@ -1,8 +1,10 @@
-use crate::types::Proof;
+use crate::types::{Proof, ProofKind};
 
 use super::traits::ZKVMEnv;
 #[cfg(any(feature = "native-risc0"))]
 use super::traits::{ZKVMProof, ZKVMProver};
+#[cfg(any(feature = "native-risc0"))]
+use super::ExecutionStats;
 use super::ProverMode;
 use anyhow::anyhow;
 use anyhow::Error;
@@ -11,15 +13,79 @@ use risc0_zkvm::serde::to_vec;
 #[cfg(any(feature = "native-risc0"))]
 use risc0_zkvm::ProverOpts;
 #[cfg(any(feature = "native-risc0"))]
-use risc0_zkvm::{default_prover, Executor, ExecutorEnv, ExecutorEnvBuilder, Prover};
-use risc0_zkvm::{serde::from_slice, Receipt};
+use risc0_zkvm::{
+    compute_image_id, default_executor, default_prover, Executor, ExecutorEnv, ExecutorEnvBuilder,
+    FakeReceipt, Prover, ReceiptClaim,
+};
+use risc0_zkvm::{serde::from_slice, InnerReceipt, Receipt};
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "native-risc0"))]
+use std::collections::HashMap;
+#[cfg(any(feature = "native-risc0"))]
+use std::io::Write;
+#[cfg(any(feature = "native-risc0"))]
+use std::sync::{Arc, Mutex};
 
 #[cfg(any(feature = "native-risc0"))]
 pub struct RiscZeroProver<'a> {
     env_builder: ExecutorEnvBuilder<'a>,
     elf: Vec<u8>,
     prover_mode: ProverMode,
+    execution_stats: Option<ExecutionStats>,
+}
+
+//Collects the guest stdout, which carries the cycle markers.
+#[cfg(any(feature = "native-risc0"))]
+#[derive(Clone, Default)]
+struct GuestOutput(Arc<Mutex<Vec<u8>>>);
+
+#[cfg(any(feature = "native-risc0"))]
+impl Write for GuestOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.0.lock() {
+            Ok(mut output) => output.extend_from_slice(buf),
+            Err(_) => return Err(std::io::Error::other("Guest output lock poisoned")),
+        };
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+//Pairs the start and end markers printed by the guest into cycles per label.
+#[cfg(any(feature = "native-risc0"))]
+fn cycle_markers(output: &[u8]) -> Vec<(String, u64)> {
+    let mut started: HashMap<String, u64> = HashMap::new();
+    let mut markers: Vec<(String, u64)> = vec![];
+
+    for line in String::from_utf8_lossy(output).lines() {
+        let (is_start, marker) = if let Some(i) = line.strip_prefix("cycle-marker-start: ") {
+            (true, i)
+        } else if let Some(i) = line.strip_prefix("cycle-marker-end: ") {
+            (false, i)
+        } else {
+            continue;
+        };
+
+        let (label, cycles) = match marker
+            .rsplit_once(' ')
+            .and_then(|(label, cycles)| Some((label, cycles.parse::<u64>().ok()?)))
+        {
+            Some(i) => i,
+            None => continue,
+        };
+
+        if is_start {
+            started.insert(label.to_string(), cycles);
+        } else if let Some(start) = started.remove(label) {
+            markers.push((label.to_string(), cycles.saturating_sub(start)));
+        }
+    }
+
+    markers
 }
 
 #[cfg(any(feature = "native-risc0"))]
@@ -30,6 +96,7 @@ impl<'a> ZKVMProver<RiscZeroProof> for RiscZeroProver<'a> {
             env_builder,
             elf,
             prover_mode,
+            execution_stats: None,
         }
     }
 
@@ -44,6 +111,10 @@ impl<'a> ZKVMProver<RiscZeroProof> for RiscZeroProver<'a> {
     }
 
     fn prove(&mut self) -> Result<RiscZeroProof, anyhow::Error> {
+        if self.prover_mode == ProverMode::ExecuteOnly {
+            return self.execute();
+        }
+
         let start_time = std::time::Instant::now(); // Start time measurement
 
         //let env_1: ExecutorEnv = self.env_builder.clone().build().map_err(|e| anyhow!(e))?;
@@ -58,6 +129,8 @@ impl<'a> ZKVMProver<RiscZeroProof> for RiscZeroProver<'a> {
             ProverMode::Compressed => ProverOpts::succinct(),
             ProverMode::NoAggregation => ProverOpts::composite(),
             ProverMode::Groth16 => ProverOpts::groth16(),
+            ProverMode::Plonk => return Err(anyhow!("Plonk proofs are not supported by risc0")),
+            ProverMode::ExecuteOnly => unreachable!("Execute only mode does not prove"),
         };
 
         let receipt = match &self.prover_mode {
@@ -75,6 +148,57 @@ impl<'a> ZKVMProver<RiscZeroProof> for RiscZeroProver<'a> {
 
         Ok(RiscZeroProof(receipt.receipt))
     }
+
+    fn execution_stats(&self) -> Option<ExecutionStats> {
+        self.execution_stats.clone()
+    }
+}
+
+#[cfg(any(feature = "native-risc0"))]
+impl<'a> RiscZeroProver<'a> {
+    //Executes the guest, the receipt returned is fake and only carries the journal.
+    fn execute(&mut self) -> Result<RiscZeroProof, anyhow::Error> {
+        let output = GuestOutput::default();
+        let env: ExecutorEnv = self
+            .env_builder
+            .stdout(output.clone())
+            .build()
+            .map_err(|e| anyhow!(e))?;
+
+        let session = default_executor()
+            .execute(env, &self.elf)
+            .map_err(|e| anyhow!("Error when executing: {:?}", e))?;
+
+        let markers = match output.0.lock() {
+            Ok(i) => cycle_markers(&i),
+            Err(_) => return Err(anyhow!("Guest output lock poisoned")),
+        };
+        let stats = ExecutionStats {
+            total_cycles: session
+                .segments
+                .iter()
+                .map(|segment| 1u64 << segment.po2)
+                .sum(),
+            user_cycles: session
+                .segments
+                .iter()
+                .map(|segment| segment.cycles as u64)
+                .sum(),
+            segments: session.segments.len() as u64,
+            tx_cycles: vec![],
+        }
+        .with_tx_markers(markers);
+        println!("Execution stats: {:?}", stats);
+        self.execution_stats = Some(stats);
+
+        let image_id = compute_image_id(&self.elf).map_err(|e| anyhow!(e))?;
+        let claim = ReceiptClaim::ok(image_id, session.journal.bytes.clone());
+
+        Ok(RiscZeroProof(Receipt::new(
+            InnerReceipt::Fake(FakeReceipt::new(claim)),
+            session.journal.bytes,
+        )))
+    }
 }
 
 #[cfg(any(feature = "native-risc0"))]
@@ -123,7 +247,15 @@ impl TryInto<Proof> for RiscZeroProof {
             .into_iter()
             .flat_map(|x| x.to_ne_bytes().to_vec())
             .collect();
-        Ok(Proof(encoded_u8))
+        //Groth16 receipts can not be verified by recursion.
+        if let InnerReceipt::Groth16(_) = &self.0.inner {
+            return Err(anyhow!("Groth16 receipts can not be submitted."));
+        }
+
+        Ok(Proof {
+            kind: ProofKind::Risc0Receipt,
+            data: encoded_u8,
+        })
     }
 }
 
@@ -132,7 +264,11 @@ impl TryFrom<Proof> for RiscZeroProof {
     type Error = anyhow::Error;
 
     fn try_from(value: Proof) -> Result<Self, Self::Error> {
-        let receipt: Receipt = from_slice(&value.0)?;
+        match value.kind {
+            ProofKind::Risc0Receipt => (),
+            _ => return Err(anyhow!("Not a risc0 proof, got {:?}.", value.kind)),
+        }
+        let receipt: Receipt = from_slice(&value.data)?;
 
         Ok(Self(receipt))
     }
@@ -160,6 +296,22 @@ impl ZKVMEnv for ZKVM {
     fn commit<T: serde::Serialize>(data: &T) {
         env::commit(data);
     }
+
+    //Printed to the guest stdout, and paired up by the prover in execute only mode.
+    fn cycle_marker_start(_label: &str) {
+        #[cfg(not(feature = "native"))]
+        println!("cycle-marker-start: {} {}", _label, env::cycle_count());
+    }
+
+    fn cycle_marker_end(_label: &str) {
+        #[cfg(not(feature = "native"))]
+        println!("cycle-marker-end: {} {}", _label, env::cycle_count());
+    }
+
+    fn proof_kind() -> ProofKind {
+        //Groth16 receipts cannot be resolved as assumptions.
+        ProofKind::Risc0Receipt
+    }
 }
 
 #[cfg(any(feature = "native-risc0"))]
@@ -1,8 +1,10 @@
 use super::traits::ZKVMEnv;
 #[cfg(any(feature = "native-sp1"))]
 use super::traits::{ZKVMProof, ZKVMProver};
+#[cfg(any(feature = "native-sp1"))]
+use super::ExecutionStats;
 use super::ProverMode;
-use crate::types::Proof;
+use crate::types::{Proof, ProofKind};
 use anyhow::anyhow;
 use anyhow::Error;
 use bincode;
@@ -15,7 +17,7 @@ use sha2::Sha256;
 #[cfg(any(feature = "native-sp1"))]
 use sp1_sdk::{
     utils, HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Prover, SP1ProvingKey,
-    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+    SP1PublicValues, SP1Stdin, SP1VerifyingKey, SP1_CIRCUIT_VERSION,
 };
 use std::borrow::Cow;
 
@@ -27,8 +29,13 @@ pub struct Sp1Prover {
     prover_mode: ProverMode,
     pk: SP1ProvingKey,
     vk: SP1VerifyingKey,
+    execution_stats: Option<ExecutionStats>,
 }
 
+//Default shard size of the sp1 prover, to report shards as segments.
+#[cfg(any(feature = "native-sp1"))]
+const SP1_SHARD_SIZE: u64 = 1 << 22;
+
 #[cfg(any(feature = "native-sp1"))]
 impl Sp1Prover {
     pub fn vk(&self) -> [u32; 8] {
@@ -41,7 +48,7 @@ impl ZKVMProver<Sp1Proof> for Sp1Prover {
     fn new(elf: Vec<u8>, prover_mode: ProverMode) -> Self {
         let mut sp1_standard_input = SP1Stdin::new();
         let sp1_client = match prover_mode {
-            ProverMode::MockProof => ProverClient::mock(),
+            ProverMode::MockProof | ProverMode::ExecuteOnly => ProverClient::mock(),
             _ => ProverClient::local(),
         };
         let (pk, vk) = sp1_client.setup(&elf);
@@ -52,6 +59,7 @@ impl ZKVMProver<Sp1Proof> for Sp1Prover {
             prover_mode,
             pk,
             vk,
+            execution_stats: None,
         }
     }
 
@@ -62,47 +70,76 @@ impl ZKVMProver<Sp1Proof> for Sp1Prover {
     }
 
     fn add_proof_for_recursion(&mut self, proof: Sp1Proof) -> Result<(), anyhow::Error> {
-        let SP1Proof::Compressed(p) = proof.0.proof else {
-            return Err(Error::msg("Compressed proof not provided"));
-        };
-
-        self.sp1_standard_input
-            .write_proof(*p.clone(), p.vk.clone());
-        Ok(())
+        match proof.proof.proof {
+            SP1Proof::Compressed(p) => {
+                self.sp1_standard_input
+                    .write_proof(*p.clone(), p.vk.clone());
+                Ok(())
+            }
+            //Mock proofs cannot be verified, so they are only taken when mocking.
+            SP1Proof::Core(shards)
+                if shards.is_empty() && self.prover_mode == ProverMode::MockProof =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::msg("Compressed proof not provided")),
+        }
     }
 
     fn prove(&mut self) -> Result<Sp1Proof, anyhow::Error> {
-        let mut sp1_input = self.sp1_standard_input.clone();
+        let sp1_input = self.sp1_standard_input.clone();
 
         let proof = match &self.prover_mode {
-            // ProverMode::MockProof => {
-            //     let (output, stats) = self.sp1_client.execute(&self.elf, sp1_input).run().unwrap();
-
-            //     Sp1Proof::Mock(output)
-            // }
-            ProverMode::Compressed => Sp1Proof(
-                self.sp1_client
-                    .prove(&self.pk, sp1_input)
-                    .compressed()
-                    .run()
-                    .expect("proof generation failed"),
-            ),
-            ProverMode::MockProof => Sp1Proof(
-                self.sp1_client
-                    .prove(&self.pk, sp1_input)
-                    .compressed()
-                    .run()
-                    .expect("proof generation failed"),
-            ),
-            _ => Sp1Proof(
-                self.sp1_client
-                    .prove(&self.pk, sp1_input)
-                    .run()
-                    .expect("proof generation failed"),
-            ),
+            ProverMode::ExecuteOnly => {
+                let (public_values, report) = self
+                    .sp1_client
+                    .execute(&self.elf, sp1_input.clone())
+                    .run()?;
+
+                let total_cycles = report.total_instruction_count();
+                let stats = ExecutionStats {
+                    total_cycles,
+                    user_cycles: total_cycles,
+                    segments: total_cycles.div_ceil(SP1_SHARD_SIZE),
+                    tx_cycles: vec![],
+                }
+                .with_tx_markers(report.cycle_tracker.clone());
+                println!("Execution stats: {:?}", stats);
+                self.execution_stats = Some(stats);
+
+                //Nothing is proven, the proof only carries the public values.
+                SP1ProofWithPublicValues {
+                    proof: SP1Proof::Core(vec![]),
+                    stdin: sp1_input,
+                    public_values,
+                    sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+                }
+            }
+            //The mock client only executes the guest, so proofs added for recursion
+            //are mock proofs as well and are not verified.
+            ProverMode::MockProof => self
+                .sp1_client
+                .prove(&self.pk, sp1_input)
+                .deferred_proof_verification(false)
+                .run()?,
+            ProverMode::NoAggregation => self.sp1_client.prove(&self.pk, sp1_input).core().run()?,
+            ProverMode::Compressed => self
+                .sp1_client
+                .prove(&self.pk, sp1_input)
+                .compressed()
+                .run()?,
+            ProverMode::Groth16 => self.sp1_client.prove(&self.pk, sp1_input).groth16().run()?,
+            ProverMode::Plonk => self.sp1_client.prove(&self.pk, sp1_input).plonk().run()?,
         };
 
-        Ok(proof)
+        Ok(Sp1Proof {
+            proof,
+            vk: self.vk.clone(),
+        })
+    }
+
+    fn execution_stats(&self) -> Option<ExecutionStats> {
+        self.execution_stats.clone()
     }
 }
 // #[cfg(any(feature = "native-sp1"))]
@@ -110,15 +147,40 @@ impl ZKVMProver<Sp1Proof> for Sp1Prover {
 // pub struct Sp1Proof(pub SP1ProofWithPublicValues);
 
 #[cfg(any(feature = "native-sp1"))]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Sp1Proof(SP1ProofWithPublicValues);
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sp1Proof {
+    proof: SP1ProofWithPublicValues,
+    //Key of the program, only trusted after matching the expected vk hash.
+    vk: SP1VerifyingKey,
+}
+
+#[cfg(any(feature = "native-sp1"))]
+impl std::fmt::Debug for Sp1Proof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sp1Proof")
+            .field("proof", &self.proof)
+            .field("vk", &self.vk.bytes32())
+            .finish()
+    }
+}
+
+//Image ids are vk hashes given as bytes, in the word order of the statement digest.
+#[cfg(any(feature = "native-sp1"))]
+fn vk_hash_from_bytes(img_id: [u8; 32]) -> [u32; 8] {
+    let mut vk_hash = [0u32; 8];
+    for (word, chunk) in vk_hash.iter_mut().zip(img_id.chunks(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    vk_hash
+}
 
 #[cfg(any(feature = "native-sp1"))]
 impl ZKVMProof for Sp1Proof {
     fn public_inputs<V: serde::Serialize + serde::de::DeserializeOwned + Clone>(
         &mut self,
     ) -> Result<V, anyhow::Error> {
-        Ok(self.0.public_values.clone().read::<V>())
+        Ok(self.proof.public_values.clone().read::<V>())
     }
 
     // fn verify(&self, img_id: [u8; 32]) -> Result<(), anyhow::Error> {
@@ -131,25 +193,34 @@ impl ZKVMProof for Sp1Proof {
         elf: Option<Vec<u8>>,
         proof_mode: ProverMode,
     ) -> Result<(), anyhow::Error> {
-        let elf = match elf {
-            Some(elf) => elf,
-            None => return Err(anyhow!("ELF is required")),
-        };
+        //Proofs submitted to a node executing without proving are still real
+        //proofs, so they are verified with a local client as well.
         let sp1_client = match proof_mode {
             ProverMode::MockProof => ProverClient::mock(),
             _ => ProverClient::local(),
         };
-        //TODO: Change this to also accept vk instead of the elf file.
-        let (_, vk) = sp1_client.setup(&elf);
-        sp1_client.verify(&self.0, &vk)?;
+        //Setup is only run when no vk hash is given.
+        let vk_hash = match (img_id, elf) {
+            (Some(img_id), _) => vk_hash_from_bytes(img_id),
+            (None, Some(elf)) => sp1_client.setup(&elf).1.hash_u32(),
+            (None, None) => return Err(anyhow!("Vk hash or ELF is required")),
+        };
+
+        if self.vk.hash_u32() != vk_hash {
+            return Err(anyhow!(
+                "Proof is not for the program of the given vk hash."
+            ));
+        }
+
+        sp1_client.verify(&self.proof, &self.vk)?;
         Ok(())
     }
 
     fn compress(&mut self) -> Result<Sp1Proof, anyhow::Error> {
         let mut new_proof = self.clone();
 
-        if let Some(groth16_proof) = &self.0.proof.clone().try_as_groth_16() {
-            new_proof.0.proof = SP1Proof::Groth16(groth16_proof.clone());
+        if let Some(groth16_proof) = &self.proof.proof.clone().try_as_groth_16() {
+            new_proof.proof.proof = SP1Proof::Groth16(groth16_proof.clone());
         } else {
             return Err(anyhow::anyhow!("Failed to create groth16 proof"));
         }
@@ -163,7 +234,13 @@ impl TryFrom<Proof> for Sp1Proof {
     type Error = anyhow::Error;
 
     fn try_from(value: Proof) -> Result<Self, Self::Error> {
-        let receipt: Sp1Proof = from_slice(&value.0)?;
+        match value.kind {
+            ProofKind::Sp1Compressed => (),
+            #[cfg(feature = "dev")]
+            ProofKind::Mock => (),
+            _ => return Err(anyhow!("Not a sp1 proof, got {:?}.", value.kind)),
+        }
+        let receipt: Sp1Proof = from_slice(&value.data)?;
         Ok(receipt)
     }
 }
@@ -184,9 +261,19 @@ impl TryInto<Proof> for Sp1Proof {
     type Error = anyhow::Error;
 
     fn try_into(self) -> Result<Proof, Self::Error> {
+        let kind = match &self.proof.proof {
+            SP1Proof::Compressed(_) => ProofKind::Sp1Compressed,
+            //Mock and execute only proofs prove nothing, so they are not tagged as compressed.
+            SP1Proof::Core(shards) if shards.is_empty() => ProofKind::Mock,
+            //Groth16 and plonk proofs can not be verified by recursion.
+            _ => return Err(anyhow!("Only compressed sp1 proofs can be submitted.")),
+        };
         let encoded_u8: Vec<u8> =
             to_vec(&self).map_err(|e| anyhow::anyhow!("Serialization error: {}", e))?;
-        Ok(Proof(encoded_u8))
+        Ok(Proof {
+            kind,
+            data: encoded_u8,
+        })
     }
 }
 
@@ -226,6 +313,42 @@ impl ZKVMEnv for SP1ZKVM {
         let byte_slice: &[u8] = serialized_data.as_ref();
         sp1_zkvm::io::commit_slice(byte_slice);
     }
+
+    //Picked up by the sp1 executor into the cycle tracker of the execution report.
+    fn cycle_marker_start(_label: &str) {
+        #[cfg(not(feature = "native"))]
+        println!("cycle-tracker-report-start: {}", _label);
+    }
+
+    fn cycle_marker_end(_label: &str) {
+        #[cfg(not(feature = "native"))]
+        println!("cycle-tracker-report-end: {}", _label);
+    }
+
+    fn proof_kind() -> ProofKind {
+        ProofKind::Sp1Compressed
+    }
+
+    //Mock proofs are only taken for recursion by the prover in mock proof mode,
+    //where deferred proofs are not verified, so they are limited to dev builds.
+    fn supports_proof_kind(kind: &ProofKind) -> bool {
+        kind == &ProofKind::Sp1Compressed || (cfg!(feature = "dev") && kind == &ProofKind::Mock)
+    }
+
+    fn verify_proof<T: Serialize>(
+        kind: &ProofKind,
+        img_id: [u32; 8],
+        public_inputs: &T,
+    ) -> Result<(), anyhow::Error> {
+        if Self::supports_proof_kind(kind) {
+            Self::verify(img_id, public_inputs)
+        } else {
+            Err(anyhow!(
+                "Proof kind {:?} cannot be verified in this zkvm.",
+                kind
+            ))
+        }
+    }
 }
 
 #[cfg(any(feature = "native-sp1"))]
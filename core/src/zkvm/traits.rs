@@ -1,6 +1,8 @@
-use crate::types::Proof as NexusProof;
+use crate::types::{Proof as NexusProof, ProofKind};
 use serde::{de::DeserializeOwned, Serialize};
 
+#[cfg(any(feature = "native"))]
+use super::ExecutionStats;
 use super::ProverMode;
 
 #[cfg(any(feature = "native"))]
@@ -9,6 +11,10 @@ pub trait ZKVMProver<R: ZKVMProof> {
     fn add_input<T: Serialize>(&mut self, input: &T) -> Result<(), anyhow::Error>;
     fn add_proof_for_recursion(&mut self, proof: R) -> Result<(), anyhow::Error>;
     fn prove(&mut self) -> Result<R, anyhow::Error>;
+    //Stats of the last execution in execute only mode.
+    fn execution_stats(&self) -> Option<ExecutionStats> {
+        None
+    }
 }
 
 #[cfg(any(feature = "native"))]
@@ -32,6 +38,31 @@ pub trait ZKVMProof: Sized {
 
 pub trait ZKVMEnv {
     fn verify<T: Serialize>(img_id: [u32; 8], public_inputs: &T) -> Result<(), anyhow::Error>;
+    //Kind of proofs verified by recursion with `verify`.
+    fn proof_kind() -> ProofKind;
+    //Proofs of other zkvms can not be verified by recursion, so only the
+    //recursion kind is supported by default.
+    fn supports_proof_kind(kind: &ProofKind) -> bool {
+        kind == &Self::proof_kind()
+    }
+    //Dispatches to the verifier of the proof kind.
+    fn verify_proof<T: Serialize>(
+        kind: &ProofKind,
+        img_id: [u32; 8],
+        public_inputs: &T,
+    ) -> Result<(), anyhow::Error> {
+        if kind == &Self::proof_kind() {
+            Self::verify(img_id, public_inputs)
+        } else {
+            Err(anyhow::anyhow!(
+                "Proof kind {:?} cannot be verified in this zkvm.",
+                kind
+            ))
+        }
+    }
     fn read_input<T: DeserializeOwned>() -> Result<T, anyhow::Error>;
     fn commit<T: Serialize>(data: &T);
+    //Marks a section of the guest, whose cycles are reported in execute only mode.
+    fn cycle_marker_start(_label: &str) {}
+    fn cycle_marker_end(_label: &str) {}
 }
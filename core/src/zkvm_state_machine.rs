@@ -2,11 +2,14 @@ use std::collections::HashMap;
 
 use crate::state::types::AccountState;
 use crate::stf::StateTransitionFunction;
+use crate::traits::NexusTransaction;
 use crate::types::{
-    AvailHeader, HeaderStore, NexusHeader, Sha256, StateUpdate, TransactionZKVM, H256,
+    AppAccountId, AvailHeader, HeaderStore, NexusHeader, Sha256, StateUpdate, TransactionZKVM,
+    TxParams, TxReceipt, H256,
 };
-use crate::utils::hasher::{Digest, ShaHasher};
+use crate::utils::merkle::merkle_root;
 use crate::zkvm::traits::ZKVMEnv;
+use anyhow::anyhow;
 use jmt::{KeyHash, RootHash};
 use serde::Serialize;
 
@@ -21,6 +24,11 @@ impl<Z: ZKVMEnv> ZKVMStateMachine<Z> {
         }
     }
 
+    pub fn with_cycle_markers(mut self, enabled: bool) -> Self {
+        self.stf = self.stf.with_cycle_markers(enabled);
+        self
+    }
+
     pub fn execute_batch(
         &self,
         new_avail_header: &AvailHeader,
@@ -34,47 +42,106 @@ impl<Z: ZKVMEnv> ZKVMStateMachine<Z> {
             0
         };
 
+        //The previous header links the state, so the host cannot pick the pre state root.
+        //An empty header store is the genesis case, which starts from the empty tree.
+        let expected_pre_state_root = match old_headers.first() {
+            Some(i) => i.state_root.clone(),
+            None => H256::zero(),
+        };
+        if state_update.pre_state_root != expected_pre_state_root {
+            return Err(anyhow!(
+                "Pre state root does not match state root of the previous header."
+            ));
+        }
+
         let mut pre_state: HashMap<[u8; 32], AccountState> = HashMap::new();
         if !txs.is_empty() {
-            //TODO: Implement multiproof to avoid verifying each leaf.
-            state_update
+            let values: HashMap<[u8; 32], Option<Vec<u8>>> = state_update
                 .pre_state
                 .iter()
-                .enumerate()
-                .try_for_each::<_, Result<(), anyhow::Error>>(
-                    |(index, (key, (account_state, proof)))| {
-                        let value = match account_state {
-                            Some(i) => Some(i.encode()),
-                            None => None,
-                        };
+                .map(|(key, account_state)| {
+                    (key.clone(), account_state.as_ref().map(|i| i.encode()))
+                })
+                .collect();
 
-                        pre_state.insert(
-                            key.clone(),
-                            account_state.clone().unwrap_or_else(AccountState::zero),
-                        );
+            state_update
+                .pre_state_proof
+                .verify(&state_update.pre_state_root, &values)?;
 
-                        proof.verify(
-                            RootHash(state_update.pre_state_root.as_fixed_slice().clone()),
-                            KeyHash(key.clone()),
-                            value,
-                        )?;
+            //Pre state is keyed by account keys in the tree, and by app account ids in the stf.
+            for tx in txs.iter() {
+                let app_account_id = tx.params.app_id();
+                let account_state = match state_update
+                    .pre_state
+                    .get(app_account_id.account_key().as_fixed_slice())
+                {
+                    Some(i) => i.clone().unwrap_or_else(AccountState::zero),
+                    None => return Err(anyhow!("Pre state not provided for account.")),
+                };
 
-                        Ok(())
-                    },
-                )?
+                pre_state.insert(app_account_id.0.clone(), account_state);
+            }
         }
 
-        let result = self
-            .stf
-            .execute_batch(new_avail_header, old_headers, txs, &pre_state)?;
+        let mut receipts: Vec<TxReceipt> = vec![];
+        let (result, storage) = self.stf.execute_batch_common(
+            new_avail_header,
+            old_headers,
+            txs,
+            &pre_state,
+            |tx_hash, result| receipts.push(TxReceipt::new(tx_hash, result.err().as_ref())),
+            StateTransitionFunction::<Z>::verify_in_zkvm,
+        )?;
 
-        //TODO verify post state root.
+        //Recompute post state root from the pre state root, instead of trusting the host.
+        let mut updates: Vec<(KeyHash, Option<Vec<u8>>)> = result
+            .into_iter()
+            .map(|(key, account_state)| {
+                let key = KeyHash(AppAccountId(key).account_key().as_fixed_slice().clone());
+                if account_state == AccountState::zero() {
+                    (key, None)
+                } else {
+                    (key, Some(account_state.encode()))
+                }
+            })
+            .chain(storage.into_iter().map(|(key, value)| {
+                if value == H256::zero() {
+                    (KeyHash(key), None)
+                } else {
+                    (KeyHash(key), Some(value.as_slice().to_vec()))
+                }
+            }))
+            .collect();
+        updates.sort_by(|a, b| a.0 .0.cmp(&b.0 .0));
 
-        let txs_encoded: Vec<u8> = parity_scale_codec::Encode::encode(&txs);
+        if updates.is_empty() {
+            if state_update.post_state_root != state_update.pre_state_root {
+                return Err(anyhow!(
+                    "Post state root changed without any state updates."
+                ));
+            }
+        } else {
+            state_update.update_proof.verify_update(
+                RootHash(state_update.pre_state_root.as_fixed_slice().clone()),
+                RootHash(state_update.post_state_root.as_fixed_slice().clone()),
+                updates,
+            )?;
+        }
 
-        let mut hasher = ShaHasher::new();
-        hasher.0.update(&txs_encoded);
-        let tx_root = hasher.finish();
+        let tx_hashes: Vec<H256> = txs.iter().map(|tx| tx.hash()).collect();
+        let tx_root = merkle_root(&tx_hashes);
+        let receipt_hashes: Vec<H256> = receipts.iter().map(|receipt| receipt.hash()).collect();
+        let receipts_root = merkle_root(&receipt_hashes);
+        //Receipts are in the same order as transactions.
+        let message_leaves: Vec<H256> = txs
+            .iter()
+            .zip(receipts.iter())
+            .filter_map(|(tx, receipt)| match &tx.params {
+                TxParams::SubmitProof(params) if receipt.success => params.message_leaf(),
+                _ => None,
+            })
+            .collect();
+        let message_root = merkle_root(&message_leaves);
 
         Ok(NexusHeader {
             parent_hash: match old_headers.first() {
@@ -83,9 +150,12 @@ impl<Z: ZKVMEnv> ZKVMStateMachine<Z> {
             },
             number,
             tx_root,
+            receipts_root,
+            message_root,
             state_root: state_update.post_state_root,
             prev_state_root: state_update.pre_state_root,
             avail_header_hash: H256::from(new_avail_header.hash().as_fixed_slice().clone()),
+            header_window: old_headers.max_size as u32,
         })
     }
 }
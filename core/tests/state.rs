@@ -0,0 +1,150 @@
+#![cfg(feature = "native")]
+
+use jmt::storage::TreeReader;
+use jmt::{KeyHash, Version};
+use nexus_core::{
+    state::{MerkleStore, MultiProof, VmState},
+    types::{AccountState, H256},
+};
+use rocksdb::{Options, DB};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_versioned_value_layout() {
+    let db_path = "./tests/db/test_versioned_value_layout";
+    if let Err(e) = std::fs::remove_dir_all(db_path) {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let mut db_options = Options::default();
+    db_options.create_if_missing(true);
+    let db = DB::open(&db_options, db_path).unwrap();
+
+    //Values were previously stored as a vector of versions under the key hash.
+    let key_hash = KeyHash([7u8; 32]);
+    let old_values: Vec<(Version, Vec<u8>)> = vec![(3, vec![3]), (1, vec![1])];
+    db.put(key_hash.0, serde_json::to_vec(&old_values).unwrap())
+        .unwrap();
+    db.put(b"version", serde_json::to_vec(&3u64).unwrap())
+        .unwrap();
+
+    let store = MerkleStore::with_db(
+        Arc::new(Mutex::new(db)),
+        Arc::new(Mutex::new(HashMap::new())),
+    );
+    assert_eq!(store.migrate_layout().unwrap(), 1);
+    //The migration only runs once.
+    assert_eq!(store.migrate_layout().unwrap(), 0);
+
+    assert_eq!(store.get_value_option(0, key_hash).unwrap(), None);
+    assert_eq!(store.get_value_option(1, key_hash).unwrap(), Some(vec![1]));
+    assert_eq!(store.get_value_option(2, key_hash).unwrap(), Some(vec![1]));
+    assert_eq!(store.get_value_option(3, key_hash).unwrap(), Some(vec![3]));
+    assert_eq!(
+        store.get_value_option(u64::MAX, key_hash).unwrap(),
+        Some(vec![3])
+    );
+    assert_eq!(store.get_value_option(3, KeyHash([8u8; 32])).unwrap(), None);
+    assert_eq!(store.get::<u64>(b"version", true).unwrap(), Some(3));
+}
+
+#[test]
+fn test_state_pruning() {
+    let db_path = "./tests/db/test_state_pruning";
+    if let Err(e) = std::fs::remove_dir_all(db_path) {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let mut state = VmState::new(db_path);
+    //Stale nodes are not indexed without a retention policy.
+    assert!(!state.pruning_enabled());
+    state.enable_pruning();
+    let key = H256::from([1u8; 32]);
+    let other_key = H256::from([2u8; 32]);
+    let mut roots = vec![];
+
+    for version in 1..=3u64 {
+        let mut account = AccountState::zero();
+        account.nonce = version;
+        let mut set = HashMap::from([(key.clone(), Some(account))]);
+        //Only written once, so it is read from the first version throughout.
+        if version == 1 {
+            set.insert(other_key.clone(), Some(AccountState::zero()));
+        }
+
+        let (tree_update, state_update) = state.update_set(set, version).unwrap();
+        state.update_version(version).unwrap();
+        state.commit(&tree_update.node_batch).unwrap();
+        state
+            .record_stale_nodes(tree_update.stale_node_index_batch.iter())
+            .unwrap();
+        roots.push(state_update.post_state_root);
+    }
+
+    assert!(state.prune(2).unwrap() > 0);
+    //Nothing is left to prune for the same retention.
+    assert_eq!(state.prune(2).unwrap(), 0);
+
+    assert_eq!(state.get_root(1).unwrap(), H256::zero());
+    assert_eq!(state.get_root(2).unwrap(), roots[1]);
+    assert_eq!(state.get_root(3).unwrap(), roots[2]);
+    assert_eq!(state.get(&key, 2).unwrap().unwrap().nonce, 2);
+    assert_eq!(state.get(&key, 3).unwrap().unwrap().nonce, 3);
+    assert_eq!(
+        state.get(&other_key, 3).unwrap(),
+        Some(AccountState::zero())
+    );
+}
+
+#[test]
+fn test_multiproof_keys() {
+    let db_path = "./tests/db/test_multiproof_keys";
+    if let Err(e) = std::fs::remove_dir_all(db_path) {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let mut state = VmState::new(db_path);
+    let keys: Vec<H256> = (1..=3u8).map(|i| H256::from([i; 32])).collect();
+    let (tree_update, _) = state
+        .update_set(
+            keys.iter()
+                .map(|key| (key.clone(), Some(AccountState::zero())))
+                .collect(),
+            1,
+        )
+        .unwrap();
+    state.commit(&tree_update.node_batch).unwrap();
+    state.update_version(1).unwrap();
+    let root = state.get_root(1).unwrap();
+
+    let mut proofs = vec![];
+    let mut values: HashMap<[u8; 32], Option<Vec<u8>>> = HashMap::new();
+    for key in keys[..2].iter() {
+        let (account, proof) = state.get_with_proof(key, 1).unwrap();
+        values.insert(key.as_fixed_slice().clone(), account.map(|i| i.encode()));
+        proofs.push((key.as_fixed_slice().clone(), proof));
+    }
+
+    MultiProof::new(proofs.clone())
+        .unwrap()
+        .verify(&root, &values)
+        .unwrap();
+
+    //Value of the second key would otherwise be taken without a proof.
+    let duplicated = MultiProof::new(vec![proofs[0].clone(), proofs[0].clone()]).unwrap();
+    assert!(duplicated.verify(&root, &values).is_err());
+
+    //Keys without a leaf in the proof.
+    let mut missing_values = values.clone();
+    missing_values.insert(
+        keys[2].as_fixed_slice().clone(),
+        Some(AccountState::zero().encode()),
+    );
+    assert!(MultiProof::new(proofs.clone())
+        .unwrap()
+        .verify(&root, &missing_values)
+        .is_err());
+    let partial = MultiProof::new(vec![proofs[0].clone()]).unwrap();
+    assert!(partial.verify(&root, &values).is_err());
+}
@@ -4,7 +4,7 @@ use adapter_sdk::{state::AdapterState, types::AdapterConfig};
 use demo_rollup_core::DemoProof;
 use methods::{ADAPTER_ELF, ADAPTER_ID};
 use nexus_core::{
-    types::{AppId, StatementDigest},
+    types::{AppId, StatementDigest, TxSigningKey},
     zkvm::{
         risczero::{RiscZeroProof, ZKVM},
         traits::{ZKVMEnv, ZKVMProof, ZKVMProver},
@@ -25,6 +25,8 @@ fn main() {
             prover_mode: ProverMode::MockProof,
             //TODO: Replace with configurable value.
             avail_url: String::from("wss://turing-rpc.avail.so:443/ws"),
+            //TODO: Replace with configurable value.
+            signing_key: TxSigningKey([1u8; 32]),
         },
     );
     let rt = tokio::runtime::Runtime::new().unwrap();
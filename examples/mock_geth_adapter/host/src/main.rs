@@ -3,8 +3,8 @@ use anyhow::{Context, Error};
 use geth_methods::{ADAPTER_ELF, ADAPTER_ID};
 use nexus_core::db::NodeDB;
 use nexus_core::types::{
-    AccountState, AccountWithProof, AppAccountId, AppId, InitAccount, NexusRollupPI, Proof,
-    StatementDigest, SubmitProof, Transaction, TxParams, TxSignature, H256,
+    AccountWithProof, AppAccountId, AppId, InitAccount, NexusRollupPI, Proof,
+    ProofKind, StatementDigest, SubmitProof, Transaction, TxParams, TxSigningKey, H256,
 };
 use nexus_core::zkvm::risczero::RiscZeroProof;
 use nexus_core::zkvm::ProverMode;
@@ -70,6 +70,8 @@ async fn main() -> Result<(), Error> {
             rollup_start_height: 606460,
             prover_mode,
             avail_url: String::from("wss://turing-rpc.avail.so:443/ws"),
+            //TODO: Replace with configurable value.
+            signing_key: TxSigningKey([1u8; 32]),
         };
         AdapterStateData {
             last_height: 0,
@@ -118,15 +120,20 @@ async fn main() -> Result<(), Error> {
                     continue;
                 }
 
-                if account_with_proof.account == AccountState::zero() {
-                    let tx = Transaction {
-                        signature: TxSignature([0u8; 64]),
-                        params: TxParams::InitAccount(InitAccount {
+                if !account_with_proof.account.is_initiated() {
+                    let signing_key = &adapter_state_data.adapter_config.signing_key;
+                    let tx = Transaction::new_signed(
+                        TxParams::InitAccount(InitAccount {
                             app_id: app_account_id.clone(),
                             statement: StatementDigest(ADAPTER_ID),
+                            statement_kind: ProofKind::Risc0Receipt,
                             start_nexus_hash: range[0],
+                            owner: signing_key.public_key(),
                         }),
-                    };
+                        //Closed accounts keep their nonce.
+                        account_with_proof.account.nonce,
+                        signing_key,
+                    );
                     match nexus_api.send_tx(tx).await {
                         Ok(i) => {
                             start_nexus_hash = Some(range[0]);
@@ -187,6 +194,8 @@ async fn main() -> Result<(), Error> {
                         app_id: app_account_id.clone(),
                         img_id: StatementDigest(ADAPTER_ID),
                         rollup_hash: Some(H256::zero()),
+                        message_root: None,
+                        storage_hash: None,
                     };
 
                     let public_input_vec = match to_vec(&public_inputs) {
@@ -212,9 +221,8 @@ async fn main() -> Result<(), Error> {
 
                     let recursive_proof = RiscZeroProof(prove_info.receipt);
 
-                    let tx = Transaction {
-                        signature: TxSignature([0u8; 64]),
-                        params: TxParams::SubmitProof(SubmitProof {
+                    let tx = Transaction::new_signed(
+                        TxParams::SubmitProof(SubmitProof {
                             app_id: app_account_id.clone(),
                             nexus_hash: range[0],
                             state_root: public_inputs.state_root.clone(),
@@ -228,8 +236,12 @@ async fn main() -> Result<(), Error> {
                             },
                             height: public_inputs.height,
                             data: None,
+                            message_root: None,
+                            storage_hash: None,
                         }),
-                    };
+                        account_with_proof.account.nonce,
+                        &adapter_state_data.adapter_config.signing_key,
+                    );
 
                     match nexus_api.send_tx(tx).await {
                         Ok(i) => {
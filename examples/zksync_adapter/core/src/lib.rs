@@ -240,6 +240,8 @@ impl STF {
             rollup_hash: Some(NexusH256::from(
                 current_batch_commitment.as_fixed_bytes().clone(),
             )),
+            message_root: None,
+            storage_hash: None,
         };
 
         Ok(proof_public_input)
@@ -285,6 +287,8 @@ impl STF {
                             app_id: i.0,
                             img_id: i.1.statement,
                             rollup_hash: Some(NexusH256::from(genesis_batch_commitment.as_fixed_bytes().clone())),
+                            message_root: None,
+                            storage_hash: None,
                         },
                         None => return Err(anyhow!("Init account details not provided which is required for first recursive proof")),
                     }
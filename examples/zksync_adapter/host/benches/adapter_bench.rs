@@ -1,4 +1,6 @@
-use nexus_core::types::{AccountState, AppAccountId, StatementDigest, H256 as NexusH256};
+use nexus_core::types::{
+    AccountState, AppAccountId, ProofKind, StatementDigest, H256 as NexusH256,
+};
 #[cfg(feature = "risc0")]
 use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
 #[cfg(feature = "sp1")]
@@ -299,6 +301,16 @@ fn create_mock_data() -> (
             start_nexus_hash: [2u8; 32],
             last_proof_height: 0,
             height: 0,
+            owner: [4u8; 32],
+            nonce: 1,
+            pending_statement: StatementDigest::zero(),
+            pending_statement_height: 0,
+            message_root: [0; 32],
+            rollup_hash: [0; 32],
+            storage_hash: [0; 32],
+            statement_kind: ProofKind::Risc0Receipt,
+            pending_statement_kind: ProofKind::Risc0Receipt,
+            generation: 0,
         },
     ));
     let new_rollup_proof = get_mock_proof();
@@ -3,7 +3,7 @@ use anyhow::{anyhow, Context, Error};
 use nexus_core::db::NodeDB;
 use nexus_core::types::{
     AccountState, AccountWithProof, AppAccountId, AppId, InitAccount, NexusRollupPI,
-    StatementDigest, SubmitProof, Transaction, TxParams, TxSignature, H256,
+    StatementDigest, SubmitProof, Transaction, TxParams, TxSigningKey, H256,
 };
 
 #[cfg(feature = "risc0")]
@@ -12,7 +12,7 @@ use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prove
 #[cfg(feature = "sp1")]
 use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
 
-use nexus_core::zkvm::traits::{ZKVMProof, ZKVMProver};
+use nexus_core::zkvm::traits::{ZKVMEnv, ZKVMProof, ZKVMProver};
 use nexus_core::zkvm::ProverMode;
 use proof_api::ProofAPIResponse;
 #[cfg(feature = "risc0")]
@@ -120,6 +120,8 @@ async fn main() -> Result<(), Error> {
                 rollup_start_height: 606460,
                 prover_mode: prover_mode.clone(),
                 avail_url: String::from("wss://turing-rpc.avail.so:443/ws"),
+                //TODO: Replace with configurable value.
+                signing_key: TxSigningKey([1u8; 32]),
             };
             AdapterStateData {
                 last_height: 0,
@@ -164,15 +166,20 @@ async fn main() -> Result<(), Error> {
     //Commenting below, as last height should be last height known to adapter, and should create proofs from that point.
     //last_height = account_with_proof.account.height;
 
-    if account_with_proof.account == AccountState::zero() {
-        let tx = Transaction {
-            signature: TxSignature([0u8; 64]),
-            params: TxParams::InitAccount(InitAccount {
+    if !account_with_proof.account.is_initiated() {
+        let signing_key = &adapter_state_data.adapter_config.signing_key;
+        let tx = Transaction::new_signed(
+            TxParams::InitAccount(InitAccount {
                 app_id: app_account_id.clone(),
                 statement: StatementDigest(ZKSYNC_ADAPTER_ID),
+                statement_kind: ZKVM::proof_kind(),
                 start_nexus_hash: account_with_proof.nexus_header.hash(),
+                owner: signing_key.public_key(),
             }),
-        };
+            //Closed accounts keep their nonce.
+            account_with_proof.account.nonce,
+            signing_key,
+        );
 
         // fs::write("./init_tx.json", serde_json::to_string(&tx).unwrap()).await;
 
@@ -225,7 +232,7 @@ async fn main() -> Result<(), Error> {
                 //Commenting below, as last height should be last height known to adapter, and should create proofs from that point.
                 //last_height = account_with_proof.account.height;
 
-                if account_with_proof.account == AccountState::zero() {
+                if !account_with_proof.account.is_initiated() {
                     println!("Account state is not initiated, restart may be required");
                     tokio::time::sleep(Duration::from_secs(2)).await;
 
@@ -325,11 +332,12 @@ async fn main() -> Result<(), Error> {
                         app_id: app_account_id.clone(),
                         img_id: StatementDigest(ZKSYNC_ADAPTER_ID),
                         rollup_hash: Some(rollup_hash),
+                        message_root: None,
+                        storage_hash: None,
                     };
 
-                    let tx = Transaction {
-                        signature: TxSignature([0u8; 64]),
-                        params: TxParams::SubmitProof(SubmitProof {
+                    let tx = Transaction::new_signed(
+                        TxParams::SubmitProof(SubmitProof {
                             app_id: app_account_id.clone(),
                             nexus_hash: range[0],
                             state_root: public_inputs.state_root.clone(),
@@ -343,8 +351,12 @@ async fn main() -> Result<(), Error> {
                             },
                             height: public_inputs.height,
                             data: public_inputs.rollup_hash.clone(),
+                            message_root: public_inputs.message_root.clone(),
+                            storage_hash: public_inputs.storage_hash.clone(),
                         }),
-                    };
+                        account_with_proof.account.nonce,
+                        &adapter_state_data.adapter_config.signing_key,
+                    );
 
                     // fs::write(
                     //     format!("./submitproof_tx_{}.json", public_inputs.height),
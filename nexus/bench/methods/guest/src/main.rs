@@ -0,0 +1,31 @@
+#![no_main]
+use jmt::{proof::SparseMerkleProof, KeyHash, RootHash};
+use nexus_core::{state::MultiProof, types::H256, utils::hasher::Sha256};
+use risc0_zkvm::guest::env;
+use std::collections::HashMap;
+
+risc0_zkvm::guest::entry!(main);
+
+//Verifies the same touched accounts either with a proof per leaf, or with a
+//single multiproof, so the cycles of both can be compared.
+fn main() {
+    let per_leaf: bool = env::read();
+    let root: H256 = env::read();
+    let values: HashMap<[u8; 32], Option<Vec<u8>>> = env::read();
+
+    if per_leaf {
+        let proofs: Vec<([u8; 32], SparseMerkleProof<Sha256>)> = env::read();
+        for (key, proof) in proofs.iter() {
+            proof
+                .verify(
+                    RootHash(root.as_fixed_slice().clone()),
+                    KeyHash(key.clone()),
+                    values.get(key).unwrap().clone(),
+                )
+                .unwrap();
+        }
+    } else {
+        let multiproof: MultiProof = env::read();
+        multiproof.verify(&root, &values).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use jmt::{proof::SparseMerkleProof, KeyHash, RootHash};
+#[cfg(feature = "risc0")]
+use multiproof_bench_methods::MULTIPROOF_BENCH_GUEST_ELF;
+#[cfg(feature = "risc0")]
+use nexus_core::zkvm::{risczero::RiscZeroProver, traits::ZKVMProver, ProverMode};
+use nexus_core::{
+    state::{vm_state::VmState, MultiProof},
+    types::{AccountState, H256},
+    utils::hasher::{Digest, Sha256},
+};
+#[cfg(feature = "risc0")]
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+const DB_PATH: &str = "./db/multiproof_bench";
+const ACCOUNTS: u32 = 1024;
+const BATCH_SIZES: [usize; 5] = [1, 8, 32, 128, 512];
+
+fn key(index: u32) -> H256 {
+    let mut hasher = Sha256::new();
+    hasher.update(index.to_be_bytes());
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    H256::from(hash)
+}
+
+fn account(index: u32) -> AccountState {
+    let mut account = AccountState::zero();
+    account.nonce = index as u64 + 1;
+    account.height = index;
+
+    account
+}
+
+//Cycles of verifying the touched accounts in the guest, executed without proving.
+#[cfg(feature = "risc0")]
+fn guest_cycles<T: Serialize>(
+    per_leaf: bool,
+    root: &H256,
+    values: &HashMap<[u8; 32], Option<Vec<u8>>>,
+    proof: &T,
+) -> Result<u64, anyhow::Error> {
+    let mut prover =
+        RiscZeroProver::new(MULTIPROOF_BENCH_GUEST_ELF.to_vec(), ProverMode::ExecuteOnly);
+    prover.add_input(&per_leaf)?;
+    prover.add_input(root)?;
+    prover.add_input(values)?;
+    prover.add_input(proof)?;
+    prover.prove()?;
+
+    match prover.execution_stats() {
+        Some(stats) => Ok(stats.user_cycles),
+        None => Err(anyhow::anyhow!("No execution stats for the bench guest.")),
+    }
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    if fs::metadata(DB_PATH).is_ok() {
+        fs::remove_dir_all(DB_PATH).expect("Failed to remove existing bench db directory");
+    }
+
+    let mut state = VmState::new(DB_PATH);
+    let (tree_update_batch, _) = state.update_set(
+        (0..ACCOUNTS)
+            .map(|index| (key(index), Some(account(index))))
+            .collect(),
+        1,
+    )?;
+    state.commit(&tree_update_batch.node_batch)?;
+    state.update_version(1)?;
+
+    let root = state.get_root(1)?;
+
+    //Guest cycles are dominated by sha256 compressions, which grow with the
+    //number of siblings hashed, so sibling count is reported with timings and
+    //the cycles of executing the verification in the guest.
+    for batch_size in BATCH_SIZES {
+        let mut proofs: Vec<([u8; 32], SparseMerkleProof<Sha256>)> = vec![];
+        let mut values: HashMap<[u8; 32], Option<Vec<u8>>> = HashMap::new();
+
+        for index in 0..batch_size as u32 {
+            let key = key(index);
+            let (account, proof) = state.get_with_proof(&key, 1)?;
+            values.insert(key.as_fixed_slice().clone(), account.map(|i| i.encode()));
+            proofs.push((key.as_fixed_slice().clone(), proof));
+        }
+
+        let per_leaf_siblings: usize = proofs.iter().map(|(_, p)| p.siblings().len()).sum();
+        let per_leaf_size = bincode::serialize(&proofs)?.len();
+        let per_leaf_start = Instant::now();
+        for (key, proof) in proofs.iter() {
+            proof.verify(
+                RootHash(root.as_fixed_slice().clone()),
+                KeyHash(key.clone()),
+                values.get(key).unwrap().clone(),
+            )?;
+        }
+        let per_leaf_duration = per_leaf_start.elapsed();
+        #[cfg(feature = "risc0")]
+        let per_leaf_cycles = guest_cycles(true, &root, &values, &proofs)?;
+
+        let multiproof = MultiProof::new(proofs)?;
+        let multiproof_start = Instant::now();
+        multiproof.verify(&root, &values)?;
+        let multiproof_duration = multiproof_start.elapsed();
+
+        println!(
+            "Touched accounts: {} | per leaf: {} siblings, {} bytes, {:?} | multiproof: {} siblings, {} bytes, {:?}",
+            batch_size,
+            per_leaf_siblings,
+            per_leaf_size,
+            per_leaf_duration,
+            multiproof.siblings.len(),
+            bincode::serialize(&multiproof)?.len(),
+            multiproof_duration,
+        );
+
+        #[cfg(feature = "risc0")]
+        {
+            let multiproof_cycles = guest_cycles(false, &root, &values, &multiproof)?;
+            println!(
+                "Touched accounts: {} | guest cycles per leaf: {} | multiproof: {} | delta: {}",
+                batch_size,
+                per_leaf_cycles,
+                multiproof_cycles,
+                multiproof_cycles as i64 - per_leaf_cycles as i64,
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -24,9 +24,9 @@ use std::time::Instant;
 use tokio::sync::Mutex;
 
 #[cfg(feature = "risc0")]
-use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
+use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, ZKVM};
 #[cfg(feature = "sp1")]
-use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
+use nexus_core::zkvm::sp1::{Sp1Proof as Proof, SP1ZKVM as ZKVM};
 
 #[cfg(any(feature = "sp1"))]
 use env_logger;
@@ -42,7 +42,9 @@ fn create_mock_data(
         ProverMode::NoAggregation => "no_aggregation",
         ProverMode::Compressed => "compressed",
         ProverMode::Groth16 => "groth16",
+        ProverMode::Plonk => "plonk",
         ProverMode::MockProof => "mock_proof",
+        ProverMode::ExecuteOnly => "execute_only",
     };
 
     let node_db_path = format!("./db/node_db_{}", prover_mode_string);
@@ -79,7 +81,7 @@ async fn bench_init_account_transactions(
     let init_account_transactions: Vec<Transaction> =
         serde_json::from_str(&file_content).unwrap();
 
-    let (proof, header, _, _) = execute_batch::<Prover, Proof, ZKVM>(
+    let (proof, header, _, _, _) = execute_batch::<Proof>(
         &init_account_transactions,
         state_machine,
         &avail_headers[1],
@@ -103,7 +105,7 @@ async fn bench_submit_proof_transactions(
     let submit_proof_transactions: Vec<Transaction> =
         serde_json::from_str(&file_content).unwrap();
 
-    let (proof, _, _, _) = execute_batch::<Prover, Proof, ZKVM>(
+    let (proof, _, _, _, _) = execute_batch::<Proof>(
         &submit_proof_transactions,
         state_machine,
         &avail_headers[2],
@@ -160,7 +162,7 @@ async fn main() -> Result<(), anyhow::Error> {
         create_mock_data(prover_mode.clone());
     let mock_txs: Vec<Transaction> = Vec::new();
 
-    let (_, header, _, _) = execute_batch::<Prover, Proof, ZKVM>(
+    let (_, header, _, _, _) = execute_batch::<Proof>(
         &mock_txs,
         &mut state_machine,
         &avail_headers[0],
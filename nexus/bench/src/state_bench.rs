@@ -0,0 +1,101 @@
+use nexus_core::{
+    state::vm_state::VmState,
+    types::{AccountState, H256},
+    utils::hasher::{Digest, Sha256},
+};
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+const DB_PATH: &str = "./db/state_bench";
+const ACCOUNTS: u32 = 10_000;
+const UPDATES: u32 = 50;
+const UPDATE_SIZE: u32 = 100;
+const READS: u32 = 1_000;
+
+fn key(index: u32) -> H256 {
+    let mut hasher = Sha256::new();
+    hasher.update(index.to_be_bytes());
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    H256::from(hash)
+}
+
+fn account(index: u32, nonce: u64) -> AccountState {
+    let mut account = AccountState::zero();
+    account.nonce = nonce;
+    account.height = index;
+
+    account
+}
+
+fn read_all(state: &VmState, version: u64) -> Result<Duration, anyhow::Error> {
+    let start = Instant::now();
+    for index in 0..READS {
+        state.get_with_proof(&key(index * (ACCOUNTS / READS)), version)?;
+    }
+
+    Ok(start.elapsed())
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    if fs::metadata(DB_PATH).is_ok() {
+        fs::remove_dir_all(DB_PATH).expect("Failed to remove existing bench db directory");
+    }
+
+    let mut state = VmState::new(DB_PATH);
+    let (tree_update_batch, _) = state.update_set(
+        (0..ACCOUNTS)
+            .map(|index| (key(index), Some(account(index, 1))))
+            .collect(),
+        1,
+    )?;
+    state.update_version(1)?;
+    state.commit(&tree_update_batch.node_batch)?;
+
+    //Each update proves the pre state of the touched accounts, so reads are
+    //included in the update timings.
+    let mut update_duration = Duration::ZERO;
+    for version in 2..(UPDATES as u64 + 2) {
+        let set: HashMap<H256, Option<AccountState>> = (0..UPDATE_SIZE)
+            .map(|i| {
+                let index = (version as u32 * UPDATE_SIZE + i) % ACCOUNTS;
+                (key(index), Some(account(index, version)))
+            })
+            .collect();
+
+        let start = Instant::now();
+        let (tree_update_batch, _) = state.update_set(set, version)?;
+        update_duration += start.elapsed();
+
+        state.update_version(version)?;
+        state.commit(&tree_update_batch.node_batch)?;
+    }
+    let latest_version = UPDATES as u64 + 1;
+
+    println!(
+        "update_set: {} updates of {} accounts over {} accounts | total {:?}, per update {:?}",
+        UPDATES,
+        UPDATE_SIZE,
+        ACCOUNTS,
+        update_duration,
+        update_duration / UPDATES,
+    );
+
+    //Reopening the db starts with an empty node cache.
+    drop(state);
+    let state = VmState::new(DB_PATH);
+    let cold_duration = read_all(&state, latest_version)?;
+    let warm_duration = read_all(&state, latest_version)?;
+
+    println!(
+        "get_with_proof: {} reads | cold cache {:?}, per read {:?} | warm cache {:?}, per read {:?}",
+        READS,
+        cold_duration,
+        cold_duration / READS,
+        warm_duration,
+        warm_duration / READS,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use crate::zkvm::ZKVMBackend;
+use crate::AvailToNexusPointer;
+use anyhow::{anyhow, Error};
+use ed25519_dalek::VerifyingKey;
+use nexus_core::{
+    db::{BatchTransaction, NodeDB},
+    state::VmState,
+    types::{
+        AccountState, GenesisConfig, HeaderStore, NexusBlock, NexusBlockWithPointers, NexusHeader,
+        StatementDigest, H256,
+    },
+};
+use relayer::Relayer;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::info;
+
+//Version of the state tree holding genesis accounts.
+const GENESIS_VERSION: u64 = 1;
+
+pub async fn load_genesis_config(
+    path: &str,
+    backend: &ZKVMBackend,
+) -> Result<GenesisConfig, Error> {
+    let file_content = fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow!("Unable to read genesis file {}: {}", path, e))?;
+
+    let config: GenesisConfig = serde_json::from_str(&file_content)?;
+    validate_genesis_config(&config, backend)?;
+
+    Ok(config)
+}
+
+//Rejects configs the chain could not progress with, as the stf would fail every batch
+//or accounts could never be updated.
+pub fn validate_genesis_config(config: &GenesisConfig, backend: &ZKVMBackend) -> Result<(), Error> {
+    if config.header_window == 0 {
+        return Err(anyhow!(
+            "Header window in genesis config has to be greater than 0."
+        ));
+    }
+
+    for account in config.accounts.iter() {
+        if !backend.supports_proof_kind(&account.statement_kind) {
+            return Err(anyhow!(
+                "Genesis account {:?} has proof kind {:?} which is not supported by the {:?} zkvm.",
+                account.app_id,
+                account.statement_kind,
+                backend
+            ));
+        }
+
+        if account.statement == StatementDigest::zero() {
+            return Err(anyhow!(
+                "Genesis account {:?} has an empty statement.",
+                account.app_id
+            ));
+        }
+
+        if VerifyingKey::from_bytes(&account.owner).is_err() {
+            return Err(anyhow!(
+                "Genesis account {:?} has an invalid owner public key.",
+                account.app_id
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+//Writes genesis state and header if the node has not processed any block yet.
+//Otherwise checks that the node was started from the same genesis.
+pub async fn init_genesis(
+    relayer_mutex: &Arc<Mutex<impl Relayer + Send + 'static>>,
+    node_db: &Arc<Mutex<NodeDB>>,
+    state: &Arc<Mutex<VmState>>,
+    mut config: GenesisConfig,
+) -> Result<NexusHeader, Error> {
+    fill_avail_start_hash(relayer_mutex, &mut config).await;
+
+    let db_lock = node_db.lock().await;
+
+    if let Some(stored_config) = db_lock.get::<GenesisConfig>(b"genesis_config")? {
+        if stored_config != config {
+            return Err(anyhow!(
+                "Genesis config does not match the genesis the node was started with."
+            ));
+        }
+
+        //Header store is built with the header window of the genesis, a different one halts the chain.
+        if let Some(header_store) = db_lock.get::<HeaderStore>(b"previous_headers")? {
+            if header_store.max_size != config.header_window as usize {
+                return Err(anyhow!(
+                    "Header window of genesis config does not match the header store of the node db."
+                ));
+            }
+        }
+
+        return match db_lock.get::<NexusHeader>(b"genesis_header")? {
+            Some(i) => Ok(i),
+            None => Err(anyhow!("Genesis header missing in node db.")),
+        };
+    }
+
+    if db_lock.get::<HeaderStore>(b"previous_headers")?.is_some() {
+        return Err(anyhow!(
+            "Node db already has blocks which were not built from a genesis. Restart with --adopt-legacy-db to adopt its first block as genesis."
+        ));
+    }
+
+    let mut state_lock = state.lock().await;
+    let state_root = if config.accounts.is_empty() {
+        H256::zero()
+    } else {
+        let mut accounts: HashMap<H256, Option<AccountState>> = HashMap::new();
+
+        for account in config.accounts.iter() {
+            let mut account_state = AccountState::zero();
+            account_state.statement = account.statement.clone();
+            account_state.statement_kind = account.statement_kind.clone();
+            account_state.start_nexus_hash = account.start_nexus_hash.as_fixed_slice().clone();
+            account_state.owner = account.owner;
+
+            if accounts
+                .insert(account.app_id.account_key(), Some(account_state))
+                .is_some()
+            {
+                return Err(anyhow!("Duplicate account in genesis config."));
+            }
+        }
+
+        let (tree_update_batch, state_update) = state_lock.update_set(accounts, GENESIS_VERSION)?;
+        state_lock.commit(&tree_update_batch.node_batch)?;
+        state_lock.update_version(GENESIS_VERSION)?;
+
+        state_update.post_state_root
+    };
+
+    //Okay to unwrap, as hash is fetched above if not set.
+    let avail_start_hash = config.avail_start_hash.clone().unwrap();
+    let header = NexusHeader {
+        parent_hash: H256::zero(),
+        prev_state_root: H256::zero(),
+        state_root,
+        tx_root: H256::zero(),
+        receipts_root: H256::zero(),
+        message_root: H256::zero(),
+        avail_header_hash: avail_start_hash.clone(),
+        number: 0,
+        header_window: config.header_window,
+    };
+    let nexus_hash = header.hash();
+
+    let mut header_store = HeaderStore::new(config.header_window as usize);
+    header_store.push_front(&header);
+
+    let mut batch_transaction = BatchTransaction::new();
+    batch_transaction.put(b"previous_headers", &header_store)?;
+    batch_transaction.put(
+        avail_start_hash.as_slice(),
+        &AvailToNexusPointer {
+            number: config.avail_start_height,
+            nexus_hash: nexus_hash.clone(),
+        },
+    )?;
+    batch_transaction.put(nexus_hash.as_slice(), &header)?;
+    batch_transaction.put(
+        &[nexus_hash.as_slice(), b"-block"].concat(),
+        &NexusBlockWithPointers {
+            block: NexusBlock {
+                header: header.clone(),
+                transactions: vec![],
+            },
+            jmt_version: if config.accounts.is_empty() {
+                0
+            } else {
+                GENESIS_VERSION
+            },
+        },
+    )?;
+    batch_transaction.put(
+        &[header.number.to_be_bytes().as_slice(), b"-block"].concat(),
+        &nexus_hash,
+    )?;
+    batch_transaction.put(b"genesis_header", &header)?;
+    batch_transaction.put(b"genesis_config", &config)?;
+    db_lock.put_batch(batch_transaction)?;
+    db_lock.set_current_root(&state_root)?;
+
+    info!(
+        genesis_hash = %hex::encode(nexus_hash.as_slice()),
+        state_root = %hex::encode(state_root.as_slice()),
+        accounts = config.accounts.len(),
+        "🌱 Genesis initialised"
+    );
+
+    Ok(header)
+}
+
+//Records the first block of a node db built before genesis configs existed as
+//its genesis, so the node can be started with a genesis config again. The config
+//has to describe that block, legacy chains always started with empty state.
+pub async fn adopt_legacy_db(
+    relayer_mutex: &Arc<Mutex<impl Relayer + Send + 'static>>,
+    node_db: &Arc<Mutex<NodeDB>>,
+    mut config: GenesisConfig,
+) -> Result<(), Error> {
+    fill_avail_start_hash(relayer_mutex, &mut config).await;
+
+    let db_lock = node_db.lock().await;
+
+    if db_lock.get::<GenesisConfig>(b"genesis_config")?.is_some()
+        || db_lock.get::<HeaderStore>(b"previous_headers")?.is_none()
+    {
+        info!("Node db has no legacy blocks, nothing to adopt.");
+        return Ok(());
+    }
+
+    if !config.accounts.is_empty() {
+        return Err(anyhow!(
+            "Legacy node db cannot be adopted with genesis accounts."
+        ));
+    }
+
+    match db_lock.get::<HeaderStore>(b"previous_headers")? {
+        Some(i) if i.max_size != config.header_window as usize => {
+            return Err(anyhow!(
+                "Header window of genesis config does not match the header store of the legacy node db."
+            ))
+        }
+        _ => (),
+    }
+
+    let first_hash =
+        match db_lock.get::<H256>(&[0u32.to_be_bytes().as_slice(), b"-block"].concat())? {
+            Some(i) => i,
+            None => return Err(anyhow!("First block missing in legacy node db.")),
+        };
+    let first_header = match db_lock.get::<NexusHeader>(first_hash.as_slice())? {
+        Some(i) => i,
+        None => return Err(anyhow!("First block header missing in legacy node db.")),
+    };
+    let first_avail_height =
+        match db_lock.get::<AvailToNexusPointer>(first_header.avail_header_hash.as_slice())? {
+            Some(i) => i.number,
+            None => {
+                return Err(anyhow!(
+                    "Avail pointer of first block missing in legacy node db."
+                ))
+            }
+        };
+
+    if first_header.parent_hash != H256::zero()
+        || Some(first_header.avail_header_hash.clone()) != config.avail_start_hash
+        || first_avail_height != config.avail_start_height
+        || first_header.header_window != config.header_window
+    {
+        return Err(anyhow!(
+            "Genesis config does not match the first block of the legacy node db."
+        ));
+    }
+
+    let mut batch_transaction = BatchTransaction::new();
+    batch_transaction.put(b"genesis_header", &first_header)?;
+    batch_transaction.put(b"genesis_config", &config)?;
+    db_lock.put_batch(batch_transaction)?;
+
+    info!(
+        genesis_hash = %hex::encode(first_hash.as_slice()),
+        avail_start_height = config.avail_start_height,
+        "🌱 Adopted first block of legacy node db as genesis"
+    );
+
+    Ok(())
+}
+
+async fn fill_avail_start_hash(
+    relayer_mutex: &Arc<Mutex<impl Relayer + Send + 'static>>,
+    config: &mut GenesisConfig,
+) {
+    if config.avail_start_hash.is_none() {
+        let relayer = relayer_mutex.lock().await;
+        config.avail_start_hash = Some(relayer.get_header_hash(config.avail_start_height).await);
+    }
+}
@@ -8,13 +8,14 @@ use nexus_core::{
     state_machine::StateMachine,
     traits::NexusTransaction,
     types::{
-        AvailHeader, HeaderStore, NexusBlock, NexusBlockWithPointers, NexusHeader,
-        Proof as NexusProof, Transaction, TransactionResult, TransactionStatus,
-        TransactionWithStatus, TransactionZKVM, TxParams, H256,
+        AvailHeader, ClosedAccount, HeaderStore, NexusBlock, NexusBlockWithPointers, NexusHeader,
+        Transaction, TransactionResult, TransactionStatus, TransactionWithStatus, TransactionZKVM,
+        TxError, TxParams, TxReceipt, DEFAULT_HEADER_WINDOW, H256,
     },
+    utils::merkle::merkle_root,
     zkvm::{
-        traits::{ZKVMEnv, ZKVMProof, ZKVMProver},
-        ProverMode,
+        traits::{ZKVMProof, ZKVMProver},
+        ExecutionStats, ProverMode,
     },
 };
 use serde_json;
@@ -23,26 +24,29 @@ use tokio::fs;
 use tracing::{debug, error, info, instrument};
 
 use crate::rpc::routes;
+use crate::zkvm::{BackendProof, ZKVMBackend};
 use avail_subxt::config::Header as HeaderTrait;
+#[cfg(any(feature = "mock"))]
+use nexus_core::zkvm::mock::MockProof;
 #[cfg(any(feature = "risc0"))]
-use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
-
+use nexus_core::zkvm::risczero::RiscZeroProof;
 #[cfg(any(feature = "sp1"))]
-use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
-
-#[cfg(any(feature = "risc0"))]
-use prover::{NEXUS_RUNTIME_ELF, NEXUS_RUNTIME_ID};
+use nexus_core::zkvm::sp1::Sp1Proof;
 pub use relayer::{Relayer, SimpleRelayer};
 use serde::{Deserialize, Serialize};
+use std::env::args;
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{env::args, fmt::Debug as DebugTrait};
 use tokio::sync::{mpsc::UnboundedReceiver, watch, Mutex};
 use tokio::time::{sleep, Duration};
 use warp::Filter;
 
+pub mod genesis;
+pub mod pruning;
 pub mod rpc;
+pub mod snapshot;
+pub mod zkvm;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AvailToNexusPointer {
     number: u32,
@@ -89,6 +93,7 @@ pub async fn relayer_handle(
 
             height
         } else {
+            //Only reached if node is started without a genesis.
             10000
         }
     };
@@ -108,67 +113,93 @@ pub async fn relayer_handle(
     info!("Exited relayer handle");
 }
 
-pub async fn execute_batch<
-    Z: ZKVMProver<P>,
-    P: ZKVMProof + Serialize + Clone + DebugTrait + TryFrom<NexusProof>,
-    E: ZKVMEnv,
->(
+pub async fn execute_batch<P: BackendProof>(
     txs: &Vec<Transaction>,
-    state_machine: &mut StateMachine<E, P>,
+    state_machine: &mut StateMachine<P::Env, P>,
     header: &AvailHeader,
     header_store: &mut HeaderStore,
     prover_mode: ProverMode,
-) -> Result<(P, NexusHeader, HashMap<H256, bool>, Option<TreeUpdateBatch>), Error>
-where
-    <P as TryFrom<NexusProof>>::Error: std::fmt::Debug,
-{
+) -> Result<
+    (
+        P,
+        NexusHeader,
+        HashMap<H256, Result<(), TxError>>,
+        Option<TreeUpdateBatch>,
+        Option<ExecutionStats>,
+    ),
+    Error,
+> {
+    let mut zkvm_prover = P::Prover::new(P::elf(), prover_mode.clone());
+
+    //Proofs are verified natively and added to the prover for recursion while
+    //the batch is executed, failed proofs are only recorded as failed txs.
     let (tree_update_batch, state_update, tx_result): (
         Option<jmt::storage::TreeUpdateBatch>,
         nexus_core::types::StateUpdate,
-        HashMap<H256, bool>,
+        HashMap<H256, Result<(), TxError>>,
     ) = state_machine
-        .execute_batch(&header, header_store, &txs)
+        .execute_batch(&header, header_store, &txs, &mut zkvm_prover, &prover_mode)
         .await?;
 
-    let (proof, result) = {
-        #[cfg(any(feature = "sp1"))]
-        let NEXUS_RUNTIME_ELF: &[u8] =
-            include_bytes!("../../prover/sp1-guest/elf/riscv32im-succinct-zkvm-elf");
-
-        let mut zkvm_prover = Z::new(NEXUS_RUNTIME_ELF.to_vec(), prover_mode);
-
-        let zkvm_txs: Result<Vec<TransactionZKVM>, anyhow::Error> = txs
+    let (proof, result, execution_stats) = {
+        let zkvm_txs: Vec<TransactionZKVM> = txs
             .iter()
-            .map(|tx| {
-                if let TxParams::SubmitProof(submit_proof_tx) = &tx.params {
-                    //TODO: Remove transactions that error out from mempool
-                    let proof = submit_proof_tx.proof.clone();
-                    let receipt: P = P::try_from(proof).unwrap();
-                    zkvm_prover.add_proof_for_recursion(receipt).unwrap();
-                }
-
-                Ok(TransactionZKVM {
-                    signature: tx.signature.clone(),
-                    params: tx.params.clone(),
-                })
+            .map(|tx| TransactionZKVM {
+                signature: tx.signature.clone(),
+                nonce: tx.nonce,
+                params: tx.params.clone(),
             })
             .collect();
 
-        let zkvm_txs = zkvm_txs?;
-
         zkvm_prover.add_input(&zkvm_txs).unwrap();
         zkvm_prover.add_input(&state_update).unwrap();
         zkvm_prover.add_input(&header).unwrap();
         zkvm_prover.add_input(&header_store).unwrap();
+        zkvm_prover.add_input(&prover_mode).unwrap();
         let mut proof = zkvm_prover.prove()?;
+        let execution_stats = zkvm_prover.execution_stats();
 
         let result: NexusHeader = proof.public_inputs()?;
-        (proof, result)
+
+        let tx_hashes: Vec<H256> = txs.iter().map(|tx| tx.hash()).collect();
+        if result.tx_root != merkle_root(&tx_hashes) {
+            return Err(anyhow!(
+                "Tx root in proof does not match transactions in batch."
+            ));
+        }
+
+        let mut receipt_hashes: Vec<H256> = vec![];
+        let mut message_leaves: Vec<H256> = vec![];
+        for (tx, tx_hash) in txs.iter().zip(tx_hashes.iter()) {
+            let tx_result = match tx_result.get(tx_hash) {
+                Some(i) => i,
+                None => return Err(anyhow!("Tx result not found for tx in batch.")),
+            };
+
+            receipt_hashes.push(TxReceipt::new(tx_hash.clone(), tx_result.as_ref().err()).hash());
+
+            if let (Ok(()), TxParams::SubmitProof(params)) = (tx_result, &tx.params) {
+                if let Some(leaf) = params.message_leaf() {
+                    message_leaves.push(leaf);
+                }
+            }
+        }
+        if result.receipts_root != merkle_root(&receipt_hashes) {
+            return Err(anyhow!(
+                "Receipts root in proof does not match execution results."
+            ));
+        }
+        if result.message_root != merkle_root(&message_leaves) {
+            return Err(anyhow!(
+                "Message root in proof does not match execution results."
+            ));
+        }
+        (proof, result, execution_stats)
     };
 
     header_store.push_front(&result);
 
-    Ok((proof, result, tx_result, tree_update_batch))
+    Ok((proof, result, tx_result, tree_update_batch, execution_stats))
 }
 
 #[instrument(
@@ -183,16 +214,20 @@ where
         receiver
     )
 )]
-pub async fn execution_engine_handle(
+pub async fn execution_engine_handle<P: BackendProof>(
     receiver: Arc<Mutex<UnboundedReceiver<Header>>>,
     node_db: Arc<Mutex<NodeDB>>,
     mempool: Mempool,
-    mut state_machine: StateMachine<ZKVM, Proof>,
+    mut state_machine: StateMachine<P::Env, P>,
     prover_mode: ProverMode,
     mut shutdown_rx: watch::Receiver<bool>,
     state: Arc<Mutex<VmState>>,
 ) -> Result<(), anyhow::Error> {
-    info!("Starting execution engine in {:?} mode", prover_mode);
+    info!(
+        "Starting execution engine in {:?} mode with {:?} zkvm",
+        prover_mode,
+        P::BACKEND
+    );
     const MAX_HEADERS: usize = 5;
     let mut header_array: Vec<Header> = Vec::new();
 
@@ -227,7 +262,7 @@ pub async fn execution_engine_handle(
                     }
                     Ok(None) => {
                         debug!("Creating new header store");
-                        HeaderStore::new(32)
+                        HeaderStore::new(DEFAULT_HEADER_WINDOW as usize)
                     }
                     Err(_) => {
                         error!("Failed to get previous headers from DB");
@@ -238,16 +273,15 @@ pub async fn execution_engine_handle(
                 }
             };
 
-            let (txs, index) = mempool.get_current_txs().await;
+            let txs = mempool.get_current_txs().await;
             info!(
                 avail_block = header.number,
                 tx_count = txs.len(),
-                mempool_index = index.unwrap_or(0),
                 "📦 Starting batch processing"
             );
 
             debug!("🔄 Beginning batch execution");
-            match execute_batch::<Prover, Proof, ZKVM>(
+            match execute_batch::<P>(
                 &txs,
                 &mut state_machine,
                 &AvailHeader::from(&header),
@@ -256,7 +290,7 @@ pub async fn execution_engine_handle(
             )
             .await
             {
-                Ok((_, result, tx_result, tree_update_batch)) => {
+                Ok((_, result, tx_result, tree_update_batch, execution_stats)) => {
                     let updated_version = state.lock().await.get_version(false)?;
                     info!(
                         nexus_block = result.number,
@@ -277,19 +311,19 @@ pub async fn execution_engine_handle(
                             txs_result: &tx_result,
                             tree_update_batch,
                             txs: &txs,
-                            mempool_index: &index,
                             updated_header_store: &old_headers,
                             jmt_version: match updated_version {
                                 Some(i) => i,
                                 None => 0,
                             },
+                            execution_stats: &execution_stats,
                         },
                     )
                     .await
                     {
                         Ok(_) => {
                             let successful_txs =
-                                tx_result.values().filter(|&&success| success).count();
+                                tx_result.values().filter(|result| result.is_ok()).count();
                             info!(
                                 nexus_block = result.number,
                                 batch_hash = %hex::encode(result.hash().as_slice()),
@@ -323,10 +357,10 @@ pub async fn execution_engine_handle(
     level = "debug",
     skip(node_db, mempool, state_machine, processed_batch_info)
 )]
-pub async fn save_batch_information<'a>(
+pub async fn save_batch_information<'a, P: BackendProof>(
     node_db: &Arc<Mutex<NodeDB>>,
     mempool: &Mempool,
-    state_machine: &mut StateMachine<ZKVM, Proof>,
+    state_machine: &mut StateMachine<P::Env, P>,
     processed_batch_info: ProcessedBatchInfo<'a>,
 ) -> Result<(), Error> {
     debug!(
@@ -344,7 +378,7 @@ pub async fn save_batch_information<'a>(
         state_machine
             .commit_state(
                 &processed_batch_info.header.state_root,
-                &tree_update.node_batch,
+                tree_update,
                 processed_batch_info.header.number,
             )
             .await?;
@@ -368,7 +402,12 @@ pub async fn save_batch_information<'a>(
 
     let mut txs_result_vec: Vec<TransactionResult> = vec![];
 
-    for (tx_hash, success) in processed_batch_info.txs_result.iter() {
+    //Stored in block order, so inclusion proofs can be generated against the tx root.
+    for tx_hash in processed_batch_info.txs.iter().map(|tx| tx.hash()) {
+        let result = match processed_batch_info.txs_result.get(&tx_hash) {
+            Some(i) => i,
+            None => return Err(anyhow!("Tx result not found for tx in batch.")),
+        };
         let db_lock = node_db.lock().await;
         let mut tx: TransactionWithStatus =
             match db_lock.get::<TransactionWithStatus>(tx_hash.as_slice())? {
@@ -377,16 +416,29 @@ pub async fn save_batch_information<'a>(
             };
 
         tx.block_hash = Some(nexus_hash.clone());
-        tx.status = if success.clone() {
+        tx.status = if result.is_ok() {
             TransactionStatus::Successful
         } else {
             TransactionStatus::Failed
         };
+        tx.error = result.clone().err();
+
+        //Closing tx is tracked for rpc, as the closed leaf does not point to it.
+        if let (Ok(()), TxParams::CloseAccount(params)) = (result, &tx.transaction.params) {
+            batch_transaction.put(
+                &[params.app_id.0.as_slice(), b"-closed"].concat(),
+                &ClosedAccount {
+                    tx_hash: tx_hash.clone(),
+                    block_hash: nexus_hash.clone(),
+                },
+            )?;
+        }
 
         batch_transaction.put(tx_hash.as_slice(), &tx);
         txs_result_vec.push(TransactionResult {
             hash: tx_hash.clone(),
-            result: success.clone(),
+            result: result.is_ok(),
+            error: result.clone().err(),
         });
     }
     batch_transaction.put(nexus_hash.as_slice(), &processed_batch_info.header);
@@ -408,15 +460,17 @@ pub async fn save_batch_information<'a>(
         .concat(),
         &nexus_hash,
     );
+    //Only recorded when the batch was executed without proving.
+    if let Some(stats) = processed_batch_info.execution_stats {
+        batch_transaction.put(&[nexus_hash.as_slice(), b"-stats"].concat(), stats)?;
+    }
     let db_lock = node_db.lock().await;
     db_lock.put_batch(batch_transaction)?;
 
     db_lock
         .set_current_root(&processed_batch_info.header.state_root)
         .unwrap();
-    if let Some(i) = processed_batch_info.mempool_index {
-        mempool.clear_upto_tx(i.clone()).await;
-    };
+    mempool.clear_txs(processed_batch_info.txs).await;
 
     Ok(())
 }
@@ -424,12 +478,12 @@ pub async fn save_batch_information<'a>(
 pub struct ProcessedBatchInfo<'a> {
     avail_header: &'a Header,
     header: &'a NexusHeader,
-    txs_result: &'a HashMap<H256, bool>,
+    txs_result: &'a HashMap<H256, Result<(), TxError>>,
     tree_update_batch: Option<TreeUpdateBatch>,
     txs: &'a Vec<Transaction>,
-    mempool_index: &'a Option<usize>,
     updated_header_store: &'a HeaderStore,
     jmt_version: u64,
+    execution_stats: &'a Option<ExecutionStats>,
 }
 
 pub fn run_server(
@@ -465,10 +519,59 @@ pub fn run_server(
     })
 }
 
-pub async fn run_nexus(
+//Builds the state machine of the configured backend, so one binary can run either zkvm.
+pub async fn run_nexus_with_backend(
+    backend: ZKVMBackend,
+    relayer_mutex: Arc<Mutex<impl Relayer + Send + 'static>>,
+    node_db: Arc<Mutex<NodeDB>>,
+    (prover_mode, server_port): (ProverMode, u32),
+    state: Arc<Mutex<VmState>>,
+    shutdown_rx: watch::Receiver<bool>,
+) -> Result<(), Error> {
+    match backend {
+        #[cfg(any(feature = "risc0"))]
+        ZKVMBackend::Risc0 => {
+            run_nexus::<RiscZeroProof>(
+                relayer_mutex,
+                node_db,
+                StateMachine::new(state.clone()),
+                (prover_mode, server_port),
+                state,
+                shutdown_rx,
+            )
+            .await
+        }
+        #[cfg(any(feature = "sp1"))]
+        ZKVMBackend::Sp1 => {
+            run_nexus::<Sp1Proof>(
+                relayer_mutex,
+                node_db,
+                StateMachine::new(state.clone()),
+                (prover_mode, server_port),
+                state,
+                shutdown_rx,
+            )
+            .await
+        }
+        #[cfg(any(feature = "mock"))]
+        ZKVMBackend::Mock => {
+            run_nexus::<MockProof>(
+                relayer_mutex,
+                node_db,
+                StateMachine::new(state.clone()),
+                (prover_mode, server_port),
+                state,
+                shutdown_rx,
+            )
+            .await
+        }
+    }
+}
+
+pub async fn run_nexus<P: BackendProof>(
     relayer_mutex: Arc<Mutex<impl Relayer + Send + 'static>>,
     node_db: Arc<Mutex<NodeDB>>,
-    mut state_machine: StateMachine<ZKVM, Proof>,
+    mut state_machine: StateMachine<P::Env, P>,
     (prover_mode, server_port): (ProverMode, u32),
     state: Arc<Mutex<VmState>>,
     mut shutdown_rx: watch::Receiver<bool>,
@@ -484,7 +587,7 @@ pub async fn run_nexus(
 
         relayer.receiver()
     };
-    let mempool = Mempool::new(node_db.clone());
+    let mempool = Mempool::new(node_db.clone(), state.clone());
     let mempool_clone = mempool.clone();
     let relayer_handle = tokio::spawn(async move {
         relayer_handle(relayer_mutex, db_clone_2, shutdown_rx_1.clone()).await
@@ -1,15 +1,16 @@
 pub use avail_subxt::Header;
-use nexus_core::{state_machine::StateMachine, zkvm::ProverMode};
+use nexus_core::types::H256;
+use nexus_core::zkvm::ProverMode;
 
-#[cfg(any(feature = "risc0"))]
-use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
-
-use host::{run_nexus, setup_components};
-#[cfg(any(feature = "sp1"))]
-use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
+use host::genesis::{adopt_legacy_db, init_genesis, load_genesis_config};
+use host::pruning::{pruning_handle, PruningConfig};
+use host::snapshot::{export_snapshot, import_snapshot, load_snapshot, save_snapshot};
+use host::zkvm::ZKVMBackend;
+use host::{run_nexus_with_backend, setup_components};
 pub use relayer::{Relayer, SimpleRelayer};
 use std::env::args;
 use std::io::Write;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::{watch, Mutex};
 use tracing::{error, info};
@@ -38,16 +39,52 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         info!("⚠️  Running in dev mode - proofs are not valid");
     }
 
-    let prover_mode = if dev_flag {
+    let execute_only_flag = args.iter().any(|arg| arg == "--execute-only");
+    if execute_only_flag {
+        info!("⚠️  Running in execute only mode - batches are not proven");
+    }
+
+    let prover_mode = if execute_only_flag {
+        ProverMode::ExecuteOnly
+    } else if dev_flag {
         ProverMode::MockProof
     } else {
         ProverMode::Compressed
     };
 
-    print_animated_logo(&prover_mode);
+    let zkvm_backend = match args
+        .iter()
+        .find(|arg| arg.starts_with("--zkvm="))
+        .map(|arg| ZKVMBackend::from_str(arg.trim_start_matches("--zkvm=")))
+    {
+        Some(Ok(i)) => i,
+        Some(Err(e)) => {
+            error!("{:?}", e);
+            return Err(e.into());
+        }
+        None => ZKVMBackend::default(),
+    };
+
+    //State of older versions is kept unless a retention is given.
+    let pruning_config = match args
+        .iter()
+        .find(|arg| arg.starts_with("--prune-keep-versions="))
+        .map(|arg| u64::from_str(arg.trim_start_matches("--prune-keep-versions=")))
+    {
+        Some(Ok(i)) => Some(PruningConfig {
+            keep_versions: i,
+            ..PruningConfig::default()
+        }),
+        Some(Err(e)) => {
+            error!("Invalid number of versions to keep: {:?}", e);
+            return Err(e.into());
+        }
+        None => None,
+    };
+
+    print_animated_logo(&prover_mode, &zkvm_backend);
 
     let (node_db, state) = setup_components("./db");
-    let mut state_machine = StateMachine::<ZKVM, Proof>::new(state.clone());
 
     let avail_rpc = args
         .iter()
@@ -55,6 +92,53 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|arg| arg.trim_start_matches("--avail-rpc="))
         .unwrap_or("wss://turing-rpc.avail.so:443/ws");
 
+    let genesis_path = args
+        .iter()
+        .find(|arg| arg.starts_with("--genesis="))
+        .map(|arg| arg.trim_start_matches("--genesis="))
+        .unwrap_or("genesis.json")
+        .to_string();
+    //Node dbs built before genesis configs existed are only started after adopting them.
+    let adopt_legacy_db_flag = args.iter().any(|arg| arg == "--adopt-legacy-db");
+
+    let snapshot_path = args
+        .iter()
+        .find(|arg| arg.starts_with("--snapshot="))
+        .map(|arg| arg.trim_start_matches("--snapshot=").to_string());
+
+    //Snapshot files are not trusted, the hash of their block is given separately.
+    let snapshot_hash = match args
+        .iter()
+        .find(|arg| arg.starts_with("--snapshot-hash="))
+        .map(|arg| {
+            hex::decode(
+                arg.trim_start_matches("--snapshot-hash=")
+                    .trim_start_matches("0x"),
+            )
+        }) {
+        Some(Ok(i)) => match <[u8; 32]>::try_from(i) {
+            Ok(hash) => Some(H256::from(hash)),
+            Err(_) => {
+                error!("Invalid snapshot hash, expected 32 bytes.");
+                return Err("Invalid snapshot hash".into());
+            }
+        },
+        Some(Err(e)) => {
+            error!("Invalid snapshot hash: {:?}", e);
+            return Err(e.into());
+        }
+        None => None,
+    };
+    if snapshot_path.is_some() && snapshot_hash.is_none() {
+        error!("A trusted --snapshot-hash= is required to import a snapshot.");
+        return Err("Missing snapshot hash".into());
+    }
+
+    let export_snapshot_path = args
+        .iter()
+        .find(|arg| arg.starts_with("--export-snapshot="))
+        .map(|arg| arg.trim_start_matches("--export-snapshot=").to_string());
+
     info!("Connecting to Avail RPC at: {}", avail_rpc);
     let relayer_mutex = Arc::new(Mutex::new(SimpleRelayer::new(avail_rpc)));
     // Shared shutdown signal using a watch channel
@@ -65,6 +149,54 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Run the tasks inside the runtime
     rt.block_on(async {
+        //Exports the state of the last block processed and exits.
+        if let Some(path) = export_snapshot_path {
+            let export_result = match export_snapshot(&node_db, &state).await {
+                Ok(snapshot) => save_snapshot(&snapshot, &path).await,
+                Err(e) => Err(e),
+            };
+            match export_result {
+                Ok(()) => info!("Exported state snapshot to {}", path),
+                Err(e) => error!("Failed to export state snapshot: {:?}", e),
+            }
+            return;
+        }
+
+        //New nodes can start from a snapshot instead of replaying from genesis.
+        if let (Some(path), Some(trusted_hash)) = (snapshot_path, snapshot_hash) {
+            let import_result = match load_snapshot(&path).await {
+                Ok(snapshot) => import_snapshot(&node_db, &state, snapshot, &trusted_hash).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = import_result {
+                error!("Failed to import state snapshot: {:?}", e);
+                return;
+            }
+        }
+
+        let genesis_result = match load_genesis_config(&genesis_path, &zkvm_backend).await {
+            Ok(config) => {
+                let adopt_result = if adopt_legacy_db_flag {
+                    adopt_legacy_db(&relayer_mutex, &node_db, config.clone()).await
+                } else {
+                    Ok(())
+                };
+                match adopt_result {
+                    Ok(()) => init_genesis(&relayer_mutex, &node_db, &state, config).await,
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = genesis_result {
+            error!("Failed to initialise genesis: {:?}", e);
+            return;
+        }
+
+        if pruning_config.is_some() {
+            state.lock().await.enable_pruning();
+        }
+
         // Spawn a task to handle the Ctrl+C signal
         let shutdown_task = tokio::spawn(async move {
             if let Err(err) = tokio::signal::ctrl_c().await {
@@ -75,13 +207,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         });
 
+        let (pruning_db, pruning_state, pruning_shutdown_rx) =
+            (node_db.clone(), state.clone(), shutdown_rx.clone());
+        let pruning_task = tokio::spawn(async move {
+            if let Some(config) = pruning_config {
+                info!(
+                    "Pruning state, keeping last {} versions",
+                    config.keep_versions
+                );
+                pruning_handle(pruning_db, pruning_state, config, pruning_shutdown_rx).await;
+            }
+        });
+
         // Spawn the main Nexus logic
         info!("Starting execution engine");
         let nexus_task = tokio::spawn(async move {
-            run_nexus(
+            run_nexus_with_backend(
+                zkvm_backend,
                 relayer_mutex,
                 node_db,
-                state_machine,
                 (prover_mode, 7000),
                 state,
                 shutdown_rx,
@@ -89,8 +233,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .await;
         });
 
-        // Wait for both tasks to complete
-        if let Err(e) = tokio::try_join!(shutdown_task, nexus_task) {
+        // Wait for all tasks to complete
+        if let Err(e) = tokio::try_join!(shutdown_task, nexus_task, pruning_task) {
             error!("Error during execution: {:?}", e);
         }
     });
@@ -99,9 +243,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn print_animated_logo(prover_mode: &ProverMode) {
+fn print_animated_logo(prover_mode: &ProverMode, zkvm_backend: &ZKVMBackend) {
     let version_line = format!("                                    ║           Version: {:8}       ║                                    ", env!("CARGO_PKG_VERSION"));
     let mode_line = format!("                                    ║      Prover Mode: {:12}    ║                                    ", format!("{:?}", prover_mode));
+    let zkvm_line = format!("                                    ║      ZKVM:        {:12}    ║                                    ", format!("{:?}", zkvm_backend));
 
     let logo = vec![
         "                                    ╔═══════════════════════════════════╗                                    ",
@@ -118,6 +263,7 @@ fn print_animated_logo(prover_mode: &ProverMode) {
         "                                    ╔═══════════════════════════════════╗                                    ",
         &version_line,
         &mode_line,
+        &zkvm_line,
         "                                    ╚═══════════════════════════════════╝                                    ",
     ];
 
@@ -128,8 +274,9 @@ fn print_animated_logo(prover_mode: &ProverMode) {
 
     // Log the startup (this will go to winston/other loggers)
     info!(
-        "Nexus node started - Version: {} Mode: {:?}",
+        "Nexus node started - Version: {} Mode: {:?} ZKVM: {:?}",
         env!("CARGO_PKG_VERSION"),
-        prover_mode
+        prover_mode,
+        zkvm_backend
     );
 }
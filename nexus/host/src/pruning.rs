@@ -0,0 +1,105 @@
+use anyhow::Error;
+use nexus_core::{
+    db::NodeDB,
+    state::VmState,
+    types::{HeaderStore, NexusBlockWithPointers},
+};
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info};
+
+#[derive(Clone, Debug)]
+pub struct PruningConfig {
+    //Number of latest state versions kept, including the current one.
+    pub keep_versions: u64,
+    pub interval: Duration,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            keep_versions: 1000,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+//Oldest version to be kept, blocks inside the header window pin their versions
+//as they can still be referenced by proofs.
+pub fn min_retained_version(
+    latest_version: u64,
+    keep_versions: u64,
+    pinned_versions: impl IntoIterator<Item = u64>,
+) -> u64 {
+    let min_version = latest_version.saturating_sub(keep_versions.saturating_sub(1));
+
+    pinned_versions
+        .into_iter()
+        .fold(min_version, |min, version| min.min(version))
+}
+
+async fn pinned_versions(node_db: &Arc<Mutex<NodeDB>>) -> Result<Vec<u64>, Error> {
+    let db_lock = node_db.lock().await;
+    let header_store = match db_lock.get::<HeaderStore>(b"previous_headers")? {
+        Some(i) => i,
+        None => return Ok(vec![]),
+    };
+
+    let mut versions = vec![];
+    for header in header_store.inner() {
+        let block_key = [header.hash().as_slice(), b"-block"].concat();
+        if let Some(block) = db_lock.get::<NexusBlockWithPointers>(&block_key)? {
+            versions.push(block.jmt_version);
+        }
+    }
+
+    Ok(versions)
+}
+
+pub async fn prune_state(
+    node_db: &Arc<Mutex<NodeDB>>,
+    state: &Arc<Mutex<VmState>>,
+    config: &PruningConfig,
+) -> Result<usize, Error> {
+    let pinned_versions = pinned_versions(node_db).await?;
+    let state_lock = state.lock().await;
+    let latest_version = match state_lock.get_version(true)? {
+        Some(i) => i,
+        None => return Ok(0),
+    };
+
+    let min_version = min_retained_version(latest_version, config.keep_versions, pinned_versions);
+    let pruned = state_lock.prune(min_version)?;
+
+    info!(
+        min_version,
+        latest_version, pruned, "Pruned stale state nodes"
+    );
+    Ok(pruned)
+}
+
+pub async fn pruning_handle(
+    node_db: Arc<Mutex<NodeDB>>,
+    state: Arc<Mutex<VmState>>,
+    config: PruningConfig,
+    mut shutdown_rx: watch::Receiver<bool>,
+) -> () {
+    loop {
+        tokio::select! {
+            _ = sleep(config.interval) => {
+                if let Err(e) = prune_state(&node_db, &state, &config).await {
+                    error!(error = ?e, "Pruning of state failed");
+                }
+            }
+            _ = shutdown_rx.changed() => {
+                if *shutdown_rx.borrow() {
+                    info!("Shutdown signal received. Stopping pruning handle...");
+                    break;
+                }
+            }
+        }
+    }
+
+    info!("Exited pruning handle");
+}
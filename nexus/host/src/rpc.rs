@@ -5,11 +5,14 @@ use nexus_core::mempool::Mempool;
 use nexus_core::state::VmState;
 use nexus_core::state_machine::StateMachine;
 use nexus_core::types::{
-    AccountState, AccountWithProof, AvailHeader, HeaderStore, NexusBlockWithPointers,
-    NexusBlockWithTransactions, NexusHeader, StatementDigest, Transaction, TransactionWithStatus,
-    H256,
+    AccountState, AccountStatus, AccountWithProof, AppAccountId, AvailHeader, ClosedAccount,
+    HeaderStore, HeaderWindow, MessageRootProof, NexusBlockWithPointers,
+    NexusBlockWithTransactions, NexusHeader, StatementDigest, StorageWithProof, Transaction,
+    TransactionWithStatus, TxInclusionProof, TxParams, TxReceipt, TxReceiptProof,
+    DEFAULT_HEADER_WINDOW, H256,
 };
 use nexus_core::utils::hasher::Sha256;
+use nexus_core::utils::merkle::merkle_proof;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
@@ -34,6 +37,15 @@ pub struct AccountStateHex {
     pub start_nexus_hash: String,
     pub last_proof_height: u32,
     pub height: u32,
+    pub owner: String,
+    pub nonce: u64,
+    pub pending_statement: String,
+    pub pending_statement_height: u32,
+    pub message_root: String,
+    pub rollup_hash: String,
+    pub storage_hash: String,
+    pub statement_kind: u8,
+    pub pending_statement_kind: u8,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -51,6 +63,7 @@ pub struct AccountWithProofHex {
     pub proof: Vec<String>,
     pub value_hash: String,
     pub nexus_header: NexusHeaderHex,
+    pub status: AccountStatus,
 }
 
 impl From<NexusHeader> for NexusHeaderHex {
@@ -73,6 +86,15 @@ impl From<AccountState> for AccountStateHex {
             start_nexus_hash: hex::encode(value.start_nexus_hash),
             last_proof_height: value.last_proof_height,
             height: value.height,
+            owner: hex::encode(value.owner),
+            nonce: value.nonce,
+            pending_statement: value.pending_statement.encode().to_string(),
+            pending_statement_height: value.pending_statement_height,
+            message_root: hex::encode(value.message_root),
+            rollup_hash: hex::encode(value.rollup_hash),
+            storage_hash: hex::encode(value.storage_hash),
+            statement_kind: value.statement_kind.code(),
+            pending_statement_kind: value.pending_statement_kind.code(),
         }
     }
 }
@@ -84,21 +106,42 @@ impl From<AccountWithProof> for AccountWithProofHex {
             proof: value.proof_hex,
             value_hash: value.value_hash_hex,
             nexus_header: NexusHeaderHex::from(value.nexus_header),
+            status: value.status,
         }
     }
 }
 
+fn account_status(
+    db: &NodeDB,
+    app_account_id: &H256,
+    account: &Option<AccountState>,
+) -> Result<AccountStatus, anyhow::Error> {
+    if account.as_ref().map_or(false, |i| i.is_initiated()) {
+        return Ok(AccountStatus::Active);
+    }
+
+    match db.get::<ClosedAccount>(&[app_account_id.as_slice(), b"-closed"].concat())? {
+        Some(_) => Ok(AccountStatus::Closed),
+        None => Ok(AccountStatus::NotFound),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
         health_check,
         submit_tx,
         tx_status,
+        tx_proof,
+        receipt_proof,
+        message_proof,
         get_block,
         get_state,
+        get_storage,
         get_state_hex,
         get_header,
-        range
+        range,
+        header_window
     ),
     components(
         schemas(
@@ -109,10 +152,23 @@ impl From<AccountWithProof> for AccountWithProofHex {
             nexus_core::types::TxParams,
             nexus_core::types::SubmitProof,
             nexus_core::types::InitAccount,
+            nexus_core::types::RotateStatement,
+            nexus_core::types::CloseAccount,
+            nexus_core::types::UpdateStorage,
+            nexus_core::types::StorageWrite,
+            nexus_core::types::StorageWithProof,
+            nexus_core::types::AccountStatus,
             nexus_core::types::NexusHeader,
             nexus_core::types::TransactionStatus,
+            nexus_core::types::TxError,
+            nexus_core::types::TxInclusionProof,
+            nexus_core::types::HeaderWindow,
+            nexus_core::types::TxReceipt,
+            nexus_core::types::TxReceiptProof,
+            nexus_core::types::MessageRootProof,
             nexus_core::state::types::AccountState,
-            nexus_core::state::types::StatementDigest
+            nexus_core::state::types::StatementDigest,
+            nexus_core::state::types::ProofKind
         )
     ),
     tags(
@@ -191,6 +247,310 @@ async fn tx_status(db: Arc<Mutex<NodeDB>>, tx_hash: H256) -> Result<WithStatus<S
     }
 }
 
+/// Get inclusion proof of a transaction against the tx root of its block
+#[utoipa::path(
+    get,
+    path = "/tx_proof",
+    tag = "nexus",
+    params(
+        ("tx_hash" = String, Query, description = "Transaction hash in hex format")
+    ),
+    responses(
+        (status = 200, description = "Inclusion proof generated", body = TxInclusionProof),
+        (status = 404, description = "Transaction not found or not yet in a block", body = String),
+        (status = 400, description = "Invalid hash format", body = String),
+        (status = 500, description = "Internal error", body = String)
+    )
+)]
+async fn tx_proof(db: Arc<Mutex<NodeDB>>, tx_hash: H256) -> Result<WithStatus<String>, Rejection> {
+    let db_lock = db.lock().await;
+
+    let block_hash = match db_lock.get::<TransactionWithStatus>(tx_hash.as_slice()) {
+        Ok(Some(TransactionWithStatus {
+            block_hash: Some(i),
+            ..
+        })) => i,
+        Ok(_) => {
+            return Ok(warp::reply::with_status(
+                "Transaction not found in a block".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let block =
+        match db_lock.get::<NexusBlockWithPointers>(&[block_hash.as_slice(), b"-block"].concat()) {
+            Ok(Some(b)) => b.block,
+            _ => {
+                return Ok(warp::reply::with_status(
+                    "Error retrieving block".to_string(),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ))
+            }
+        };
+
+    let tx_hashes: Vec<H256> = block
+        .transactions
+        .iter()
+        .map(|tx| tx.hash.clone())
+        .collect();
+    let index = match tx_hashes.iter().position(|hash| hash == &tx_hash) {
+        Some(i) => i,
+        None => {
+            return Ok(warp::reply::with_status(
+                "Transaction not found in block".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+    };
+
+    let proof = match merkle_proof(&tx_hashes, index) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let response = TxInclusionProof {
+        tx_hash,
+        block_hash,
+        tx_root: block.header.tx_root,
+        index: index as u32,
+        leaf_count: tx_hashes.len() as u32,
+        proof,
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(serialized_response) => Ok(warp::reply::with_status(
+            serialized_response,
+            warp::http::StatusCode::OK,
+        )),
+        Err(_) => Ok(warp::reply::with_status(
+            "Internal encoding error".to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Get receipt of a transaction with inclusion proof against the receipts root of its block
+#[utoipa::path(
+    get,
+    path = "/receipt_proof",
+    tag = "nexus",
+    params(
+        ("tx_hash" = String, Query, description = "Transaction hash in hex format")
+    ),
+    responses(
+        (status = 200, description = "Receipt proof generated", body = TxReceiptProof),
+        (status = 404, description = "Transaction not found or not yet in a block", body = String),
+        (status = 400, description = "Invalid hash format", body = String),
+        (status = 500, description = "Internal error", body = String)
+    )
+)]
+async fn receipt_proof(
+    db: Arc<Mutex<NodeDB>>,
+    tx_hash: H256,
+) -> Result<WithStatus<String>, Rejection> {
+    let db_lock = db.lock().await;
+
+    let block_hash = match db_lock.get::<TransactionWithStatus>(tx_hash.as_slice()) {
+        Ok(Some(TransactionWithStatus {
+            block_hash: Some(i),
+            ..
+        })) => i,
+        Ok(_) => {
+            return Ok(warp::reply::with_status(
+                "Transaction not found in a block".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let block =
+        match db_lock.get::<NexusBlockWithPointers>(&[block_hash.as_slice(), b"-block"].concat()) {
+            Ok(Some(b)) => b.block,
+            _ => {
+                return Ok(warp::reply::with_status(
+                    "Error retrieving block".to_string(),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ))
+            }
+        };
+
+    let receipts: Vec<TxReceipt> = block
+        .transactions
+        .iter()
+        .map(|tx| TxReceipt::new(tx.hash.clone(), tx.error.as_ref()))
+        .collect();
+    let index = match receipts
+        .iter()
+        .position(|receipt| receipt.tx_hash == tx_hash)
+    {
+        Some(i) => i,
+        None => {
+            return Ok(warp::reply::with_status(
+                "Transaction not found in block".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+    };
+
+    let receipt_hashes: Vec<H256> = receipts.iter().map(|receipt| receipt.hash()).collect();
+    let proof = match merkle_proof(&receipt_hashes, index) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let response = TxReceiptProof {
+        receipt: receipts[index].clone(),
+        block_hash,
+        receipts_root: block.header.receipts_root,
+        index: index as u32,
+        leaf_count: receipts.len() as u32,
+        proof,
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(serialized_response) => Ok(warp::reply::with_status(
+            serialized_response,
+            warp::http::StatusCode::OK,
+        )),
+        Err(_) => Ok(warp::reply::with_status(
+            "Internal encoding error".to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Get proof that the message root exported by an account is part of the message root of a block
+#[utoipa::path(
+    get,
+    path = "/message_proof",
+    tag = "nexus",
+    params(
+        ("app_account_id" = String, Query, description = "App account ID in hex format"),
+        ("block_hash" = String, Query, description = "Nexus block hash in hex format")
+    ),
+    responses(
+        (status = 200, description = "Message root proof generated", body = MessageRootProof),
+        (status = 404, description = "Block not found or no messages exported by account in block", body = String),
+        (status = 400, description = "Invalid hash format", body = String),
+        (status = 500, description = "Internal error", body = String)
+    )
+)]
+async fn message_proof(
+    db: Arc<Mutex<NodeDB>>,
+    app_account_id: H256,
+    block_hash: H256,
+) -> Result<WithStatus<String>, Rejection> {
+    let db_lock = db.lock().await;
+
+    let block =
+        match db_lock.get::<NexusBlockWithPointers>(&[block_hash.as_slice(), b"-block"].concat()) {
+            Ok(Some(b)) => b.block,
+            Ok(None) => {
+                return Ok(warp::reply::with_status(
+                    "Block not found".to_string(),
+                    warp::http::StatusCode::NOT_FOUND,
+                ))
+            }
+            Err(_) => {
+                return Ok(warp::reply::with_status(
+                    "Error retrieving block".to_string(),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ))
+            }
+        };
+
+    //Leaves are in the order of successful submit proof txs in the block.
+    let mut leaves: Vec<(AppAccountId, H256, H256)> = vec![];
+    for tx_result in block.transactions.iter().filter(|tx| tx.result) {
+        let tx = match db_lock.get::<TransactionWithStatus>(tx_result.hash.as_slice()) {
+            Ok(Some(i)) => i,
+            _ => {
+                return Ok(warp::reply::with_status(
+                    "Error retrieving transaction".to_string(),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ))
+            }
+        };
+
+        if let TxParams::SubmitProof(params) = tx.transaction.params {
+            if let (Some(leaf), Some(message_root)) =
+                (params.message_leaf(), params.message_root.clone())
+            {
+                leaves.push((params.app_id, message_root, leaf));
+            }
+        }
+    }
+
+    //Account state holds the message root of the last proof in the block.
+    let index = match leaves
+        .iter()
+        .rposition(|(app_id, _, _)| app_id.as_h256() == app_account_id)
+    {
+        Some(i) => i,
+        None => {
+            return Ok(warp::reply::with_status(
+                "No messages exported by account in block".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+    };
+
+    let leaf_hashes: Vec<H256> = leaves.iter().map(|(_, _, leaf)| leaf.clone()).collect();
+    let proof = match merkle_proof(&leaf_hashes, index) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let response = MessageRootProof {
+        app_id: leaves[index].0.clone(),
+        account_message_root: leaves[index].1.clone(),
+        block_hash,
+        message_root: block.header.message_root,
+        index: index as u32,
+        leaf_count: leaf_hashes.len() as u32,
+        proof,
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(serialized_response) => Ok(warp::reply::with_status(
+            serialized_response,
+            warp::http::StatusCode::OK,
+        )),
+        Err(_) => Ok(warp::reply::with_status(
+            "Internal encoding error".to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
 /// Get block by hash, number or latest
 #[utoipa::path(
     get,
@@ -334,7 +694,7 @@ async fn get_state(
 
     let header_store: HeaderStore = match db_lock.get(b"previous_headers") {
         Ok(Some(i)) => i,
-        Ok(None) => HeaderStore::new(32),
+        Ok(None) => HeaderStore::new(DEFAULT_HEADER_WINDOW as usize),
         Err(_) => {
             return Ok(warp::reply::with_status(
                 "Header store error".to_string(),
@@ -373,7 +733,8 @@ async fn get_state(
         },
     };
 
-    let (account_option, proof) = match state_lock.get_with_proof(app_account_id, version) {
+    let account_key = AppAccountId::from(app_account_id.clone()).account_key();
+    let (account_option, proof) = match state_lock.get_with_proof(&account_key, version) {
         Ok(i) => i,
         Err(_) => {
             return Ok(warp::reply::with_status(
@@ -392,6 +753,15 @@ async fn get_state(
         }
     };
 
+    let status = match account_status(&db_lock, app_account_id, &account_option) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal db error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
     let account = account_option.unwrap_or_else(AccountState::zero);
     let siblings: Vec<[u8; 32]> = proof
         .siblings()
@@ -417,6 +787,7 @@ async fn get_state(
         proof_hex: siblings.iter().map(|s| hex::encode(s)).collect(),
         value_hash_hex: hex::encode(value_hash),
         nexus_state_root_hex: hex::encode(root.as_fixed_slice()),
+        status,
     };
 
     let serialized_response = match serde_json::to_string(&response) {
@@ -435,6 +806,143 @@ async fn get_state(
     ))
 }
 
+/// Get value of a storage slot of an account and proof against state root at any height.
+#[utoipa::path(
+    get,
+    path = "/storage",
+    tag = "nexus",
+    params(
+        ("app_account_id" = String, Query, description = "Account ID in hex format"),
+        ("slot" = String, Query, description = "Storage slot in hex format"),
+        ("block_hash" = Option<String>, Query, description = "Optional block hash in hex format. If not provided, uses latest state")
+    ),
+    responses(
+        (status = 200, description = "Storage slot proof generated", body = StorageWithProof),
+        (status = 404, description = "Block or headers not found", body = String),
+        (status = 400, description = "Invalid hash format", body = String),
+        (status = 500, description = "Internal error", body = String)
+    )
+)]
+async fn get_storage(
+    db: Arc<Mutex<NodeDB>>,
+    state: Arc<Mutex<VmState>>,
+    app_account_id: H256,
+    slot: H256,
+    block_hash: Option<H256>,
+) -> Result<WithStatus<String>, Rejection> {
+    let state_lock = state.lock().await;
+    let db_lock = db.lock().await;
+
+    let header_store: HeaderStore = match db_lock.get(b"previous_headers") {
+        Ok(Some(i)) => i,
+        Ok(None) => HeaderStore::new(DEFAULT_HEADER_WINDOW as usize),
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Header store error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let (version, nexus_header) = match block_hash {
+        Some(i) => {
+            match db_lock.get::<NexusBlockWithPointers>(&[i.as_slice(), b"-block"].concat()) {
+                Ok(Some(i)) => (i.jmt_version, i.block.header),
+                Ok(None) => {
+                    return Ok(warp::reply::with_status(
+                        "Block hash not found".to_string(),
+                        warp::http::StatusCode::NOT_FOUND,
+                    ))
+                }
+                Err(_) => {
+                    return Ok(warp::reply::with_status(
+                        "Internal db error".to_string(),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ))
+                }
+            }
+        }
+        None => {
+            let version = match state_lock.get_version(true) {
+                Ok(Some(i)) => i,
+                Ok(None) => 0,
+                Err(_) => {
+                    return Ok(warp::reply::with_status(
+                        "Internal db error".to_string(),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ))
+                }
+            };
+
+            match header_store.first() {
+                Some(i) => (version, i.clone()),
+                None => {
+                    return Ok(warp::reply::with_status(
+                        "No headers available.".to_string(),
+                        warp::http::StatusCode::NOT_FOUND,
+                    ))
+                }
+            }
+        }
+    };
+
+    //Slots are read for the current generation of the account.
+    let app_account = AppAccountId::from(app_account_id);
+    let generation = match state_lock.get(&app_account.account_key(), version) {
+        Ok(account) => account.map_or(0, |i| i.generation),
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+    let key = app_account.storage_key(generation, &slot);
+    let (value, proof) = match state_lock.get_storage_with_proof(&key, version) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+    let root = match state_lock.get_root(version) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let response = StorageWithProof {
+        app_id: app_account,
+        slot,
+        key,
+        value: value.unwrap_or_else(H256::zero),
+        proof: proof
+            .siblings()
+            .iter()
+            .map(|s| H256::from(s.hash::<Sha256>()))
+            .collect(),
+        state_root: root,
+        nexus_header,
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(serialized_response) => Ok(warp::reply::with_status(
+            serialized_response,
+            warp::http::StatusCode::OK,
+        )),
+        Err(_) => Ok(warp::reply::with_status(
+            "Internal encoding error".to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
 /// Get account state in hex format
 #[utoipa::path(
     get,
@@ -460,7 +968,7 @@ async fn get_state_hex(
 
     let header_store: HeaderStore = match db_lock.get(b"previous_headers") {
         Ok(Some(i)) => i,
-        Ok(None) => HeaderStore::new(32),
+        Ok(None) => HeaderStore::new(DEFAULT_HEADER_WINDOW as usize),
         Err(_) => {
             return Ok(warp::reply::with_status(
                 "Header store error".to_string(),
@@ -479,7 +987,8 @@ async fn get_state_hex(
         }
     };
 
-    let (account_option, proof) = match state_lock.get_with_proof(app_account_id, current_version) {
+    let account_key = AppAccountId::from(app_account_id.clone()).account_key();
+    let (account_option, proof) = match state_lock.get_with_proof(&account_key, current_version) {
         Ok(i) => i,
         Err(_) => {
             return Ok(warp::reply::with_status(
@@ -498,6 +1007,15 @@ async fn get_state_hex(
         }
     };
 
+    let status = match account_status(&db_lock, app_account_id, &account_option) {
+        Ok(i) => i,
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Internal db error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
     let account = account_option.unwrap_or_else(AccountState::zero);
     let siblings: Vec<[u8; 32]> = proof
         .siblings()
@@ -523,6 +1041,7 @@ async fn get_state_hex(
         proof_hex: siblings.iter().map(|s| hex::encode(s)).collect(),
         value_hash_hex: hex::encode(value_hash),
         nexus_state_root_hex: hex::encode(root.as_fixed_slice()),
+        status,
     };
 
     let response = AccountWithProofHex::from(account_with_proof);
@@ -627,7 +1146,7 @@ async fn range(db: Arc<Mutex<NodeDB>>) -> Result<WithStatus<String>, Rejection>
 
     let header_store: HeaderStore = match db_lock.get(b"previous_headers") {
         Ok(Some(i)) => i,
-        Ok(None) => HeaderStore::new(32),
+        Ok(None) => HeaderStore::new(DEFAULT_HEADER_WINDOW as usize),
         Err(_) => {
             return Ok(warp::reply::with_status(
                 "Header store error".to_string(),
@@ -653,6 +1172,64 @@ async fn range(db: Arc<Mutex<NodeDB>>) -> Result<WithStatus<String>, Rejection>
     ))
 }
 
+/// Get the header window proofs can be anchored to.
+#[utoipa::path(
+    get,
+    path = "/header_window",
+    tag = "nexus",
+    responses(
+        (status = 200, description = "Header window retrieved successfully", body = HeaderWindow),
+        (status = 404, description = "No headers available", body = String),
+        (status = 500, description = "Internal error", body = String)
+    )
+)]
+async fn header_window(db: Arc<Mutex<NodeDB>>) -> Result<WithStatus<String>, Rejection> {
+    let db_lock = db.lock().await;
+
+    let header_store: HeaderStore = match db_lock.get(b"previous_headers") {
+        Ok(Some(i)) => i,
+        Ok(None) => {
+            return Ok(warp::reply::with_status(
+                "No headers available.".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+        Err(_) => {
+            return Ok(warp::reply::with_status(
+                "Header store error".to_string(),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    let (latest_height, oldest_height) = match (header_store.first(), header_store.inner().last()) {
+        (Some(latest), Some(oldest)) => (latest.number, oldest.number),
+        _ => {
+            return Ok(warp::reply::with_status(
+                "No headers available.".to_string(),
+                warp::http::StatusCode::NOT_FOUND,
+            ))
+        }
+    };
+
+    let response = HeaderWindow {
+        header_window: header_store.max_size as u32,
+        latest_height,
+        oldest_height,
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(serialized_response) => Ok(warp::reply::with_status(
+            serialized_response,
+            warp::http::StatusCode::OK,
+        )),
+        Err(_) => Ok(warp::reply::with_status(
+            "Internal encoding error".to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
 pub fn routes(
     mempool: Mempool,
     db: Arc<Mutex<NodeDB>>,
@@ -665,6 +1242,12 @@ pub fn routes(
     let db_clone_3 = db.clone();
     let db_clone_4 = db.clone();
     let db_clone_5 = db.clone();
+    let db_clone_6 = db.clone();
+    let db_clone_7 = db.clone();
+    let db_clone_8 = db.clone();
+    let db_clone_9 = db.clone();
+    let db_clone_10 = db.clone();
+    let vm_state_clone_2 = vm_state.clone();
 
     let health_check = warp::path("health")
         .and(warp::get())
@@ -700,6 +1283,85 @@ pub fn routes(
             },
         );
 
+    let tx_proof = warp::path("tx_proof")
+        .and(warp::get())
+        .and(warp::any().map(move || db_clone_6.clone()))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            |db: Arc<Mutex<NodeDB>>, params: HashMap<String, String>| async move {
+                match params.get("tx_hash") {
+                    Some(hash_str) => {
+                        let tx_hash = H256::try_from(hash_str.as_str());
+                        match tx_hash {
+                            Ok(hash) => tx_proof(db, hash).await,
+                            Err(_) => Ok(warp::reply::with_status(
+                                "Invalid hash".to_string(),
+                                warp::http::StatusCode::BAD_REQUEST,
+                            )),
+                        }
+                    }
+                    None => Ok(warp::reply::with_status(
+                        "Hash parameter not provided".to_string(),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    )),
+                }
+            },
+        );
+
+    let receipt_proof = warp::path("receipt_proof")
+        .and(warp::get())
+        .and(warp::any().map(move || db_clone_7.clone()))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            |db: Arc<Mutex<NodeDB>>, params: HashMap<String, String>| async move {
+                match params.get("tx_hash") {
+                    Some(hash_str) => {
+                        let tx_hash = H256::try_from(hash_str.as_str());
+                        match tx_hash {
+                            Ok(hash) => receipt_proof(db, hash).await,
+                            Err(_) => Ok(warp::reply::with_status(
+                                "Invalid hash".to_string(),
+                                warp::http::StatusCode::BAD_REQUEST,
+                            )),
+                        }
+                    }
+                    None => Ok(warp::reply::with_status(
+                        "Hash parameter not provided".to_string(),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    )),
+                }
+            },
+        );
+
+    let message_proof = warp::path("message_proof")
+        .and(warp::get())
+        .and(warp::any().map(move || db_clone_9.clone()))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            |db: Arc<Mutex<NodeDB>>, params: HashMap<String, String>| async move {
+                match (params.get("app_account_id"), params.get("block_hash")) {
+                    (Some(app_account_id_str), Some(block_hash_str)) => {
+                        match (
+                            H256::try_from(app_account_id_str.as_str()),
+                            H256::try_from(block_hash_str.as_str()),
+                        ) {
+                            (Ok(app_account_id), Ok(block_hash)) => {
+                                message_proof(db, app_account_id, block_hash).await
+                            }
+                            _ => Ok(warp::reply::with_status(
+                                "Invalid hash".to_string(),
+                                warp::http::StatusCode::BAD_REQUEST,
+                            )),
+                        }
+                    }
+                    _ => Ok(warp::reply::with_status(
+                        "App account id or block hash parameter not provided".to_string(),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    )),
+                }
+            },
+        );
+
     let block = warp::path("block")
         .and(warp::get())
         .and(warp::any().map(move || db_clone_5.clone()))
@@ -743,6 +1405,11 @@ pub fn routes(
         .and(warp::any().map(move || db_clone_2.clone()))
         .and_then(range);
 
+    let header_window = warp::path("header_window")
+        .and(warp::get())
+        .and(warp::any().map(move || db_clone_8.clone()))
+        .and_then(header_window);
+
     let header = warp::path("header")
         .and(warp::get())
         .and(warp::any().map(move || db_clone.clone()))
@@ -808,6 +1475,50 @@ pub fn routes(
             },
         );
 
+    let storage = warp::path("storage")
+        .and(warp::get())
+        .and(warp::any().map(move || db_clone_10.clone()))
+        .and(warp::any().map(move || vm_state_clone_2.clone()))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            |db: Arc<Mutex<NodeDB>>,
+             vm_state: Arc<Mutex<VmState>>,
+             params: HashMap<String, String>| async move {
+                match (params.get("app_account_id"), params.get("slot")) {
+                    (Some(id_str), Some(slot_str)) => {
+                        let block_hash = match params.get("block_hash") {
+                            Some(i) => match H256::try_from(i.as_str()) {
+                                Ok(i) => Some(i),
+                                Err(_) => {
+                                    return Ok(warp::reply::with_status(
+                                        "Invalid hash".to_string(),
+                                        warp::http::StatusCode::BAD_REQUEST,
+                                    ))
+                                }
+                            },
+                            None => None,
+                        };
+                        match (
+                            H256::try_from(id_str.as_str()),
+                            H256::try_from(slot_str.as_str()),
+                        ) {
+                            (Ok(app_account_id), Ok(slot)) => {
+                                get_storage(db, vm_state, app_account_id, slot, block_hash).await
+                            }
+                            _ => Ok(warp::reply::with_status(
+                                "Invalid hash".to_string(),
+                                warp::http::StatusCode::BAD_REQUEST,
+                            )),
+                        }
+                    }
+                    _ => Ok(warp::reply::with_status(
+                        "App account id or slot not provided".to_string(),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    )),
+                }
+            },
+        );
+
     let account_hex = warp::path("account-hex")
         .and(warp::get())
         .and(warp::any().map(move || db_clone_3.clone()))
@@ -850,10 +1561,15 @@ pub fn routes(
 
     tx.or(health_check)
         .or(tx_status)
+        .or(tx_proof)
+        .or(receipt_proof)
+        .or(message_proof)
         .or(block)
         .or(submit_batch)
         .or(header)
+        .or(header_window)
         .or(account)
+        .or(storage)
         .or(account_hex)
         .or(api_doc)
         .or(swagger_ui)
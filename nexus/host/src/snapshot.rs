@@ -0,0 +1,225 @@
+use crate::AvailToNexusPointer;
+use anyhow::{anyhow, Error};
+use jmt::{proof::SparseMerkleRangeProof, KeyHash, OwnedValue};
+use nexus_core::{
+    db::{BatchTransaction, NodeDB},
+    state::VmState,
+    types::{GenesisConfig, HeaderStore, NexusBlockWithPointers, NexusHeader, H256},
+    utils::hasher::Sha256,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::info;
+
+//Number of leaves proven together against the state root.
+const SNAPSHOT_CHUNK_SIZE: usize = 1000;
+
+//State at the version of the last block, with what the node db needs to
+//continue executing from the block after it.
+#[derive(Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub block: NexusBlockWithPointers,
+    pub header_store: HeaderStore,
+    pub avail_height: u32,
+    pub genesis_config: Option<GenesisConfig>,
+    pub genesis_header: Option<NexusHeader>,
+    pub chunks: Vec<(Vec<(KeyHash, OwnedValue)>, SparseMerkleRangeProof<Sha256>)>,
+}
+
+pub async fn export_snapshot(
+    node_db: &Arc<Mutex<NodeDB>>,
+    state: &Arc<Mutex<VmState>>,
+) -> Result<StateSnapshot, Error> {
+    let (block, header_store, avail_height, genesis_config, genesis_header) = {
+        let db_lock = node_db.lock().await;
+        let header_store = match db_lock.get::<HeaderStore>(b"previous_headers")? {
+            Some(i) => i,
+            None => return Err(anyhow!("No blocks in node db to snapshot.")),
+        };
+        let header = match header_store.first() {
+            Some(i) => i.clone(),
+            None => return Err(anyhow!("No blocks in node db to snapshot.")),
+        };
+
+        let block = match db_lock
+            .get::<NexusBlockWithPointers>(&[header.hash().as_slice(), b"-block"].concat())?
+        {
+            Some(i) => i,
+            None => return Err(anyhow!("Last block missing in node db.")),
+        };
+        let avail_height =
+            match db_lock.get::<AvailToNexusPointer>(header.avail_header_hash.as_slice())? {
+                Some(i) => i.number,
+                None => return Err(anyhow!("Avail pointer of last block missing in node db.")),
+            };
+
+        (
+            block,
+            header_store,
+            avail_height,
+            db_lock.get::<GenesisConfig>(b"genesis_config")?,
+            db_lock.get::<NexusHeader>(b"genesis_header")?,
+        )
+    };
+
+    let mut chunks = vec![];
+    //Empty state has no leaves to export.
+    if block.block.header.state_root != H256::zero() {
+        let state_lock = state.lock().await;
+        let mut start_after: Option<KeyHash> = None;
+
+        while let Some((leaves, proof)) =
+            state_lock.get_leaves_chunk(block.jmt_version, start_after, SNAPSHOT_CHUNK_SIZE)?
+        {
+            start_after = leaves.last().map(|(key_hash, _)| *key_hash);
+            chunks.push((leaves, proof));
+        }
+    }
+
+    info!(
+        nexus_block = block.block.header.number,
+        jmt_version = block.jmt_version,
+        chunks = chunks.len(),
+        "Exported state snapshot"
+    );
+
+    Ok(StateSnapshot {
+        block,
+        header_store,
+        avail_height,
+        genesis_config,
+        genesis_header,
+        chunks,
+    })
+}
+
+//Restores state and the last block of a snapshot into a new node. The snapshot
+//file is not trusted, its block has to match the hash given by the operator and
+//state is verified against that block's state root before anything is written
+//to the node db.
+pub async fn import_snapshot(
+    node_db: &Arc<Mutex<NodeDB>>,
+    state: &Arc<Mutex<VmState>>,
+    snapshot: StateSnapshot,
+    trusted_hash: &H256,
+) -> Result<NexusHeader, Error> {
+    let db_lock = node_db.lock().await;
+
+    if db_lock.get::<HeaderStore>(b"previous_headers")?.is_some() {
+        return Err(anyhow!(
+            "Node db already has blocks, snapshots can only be imported into a new node."
+        ));
+    }
+
+    let header = snapshot.block.block.header.clone();
+    let nexus_hash = header.hash();
+    if &nexus_hash != trusted_hash {
+        return Err(anyhow!(
+            "Snapshot block {:?} does not match the trusted hash {:?}.",
+            nexus_hash,
+            trusted_hash
+        ));
+    }
+
+    check_header_store(&snapshot.header_store, &header)?;
+
+    if header.state_root != H256::zero() {
+        let mut state_lock = state.lock().await;
+        state_lock.restore(
+            snapshot.block.jmt_version,
+            header.state_root.clone(),
+            snapshot.chunks,
+        )?;
+    } else if !snapshot.chunks.is_empty() {
+        return Err(anyhow!("Snapshot has state for an empty state root."));
+    }
+
+    let mut batch_transaction = BatchTransaction::new();
+    batch_transaction.put(b"previous_headers", &snapshot.header_store)?;
+    batch_transaction.put(
+        header.avail_header_hash.as_slice(),
+        &AvailToNexusPointer {
+            number: snapshot.avail_height,
+            nexus_hash: nexus_hash.clone(),
+        },
+    )?;
+    batch_transaction.put(nexus_hash.as_slice(), &header)?;
+    batch_transaction.put(
+        &[nexus_hash.as_slice(), b"-block"].concat(),
+        &snapshot.block,
+    )?;
+    batch_transaction.put(
+        &[header.number.to_be_bytes().as_slice(), b"-block"].concat(),
+        &nexus_hash,
+    )?;
+    //Kept so the node can still check it is started with the same genesis.
+    if let Some(config) = &snapshot.genesis_config {
+        batch_transaction.put(b"genesis_config", config)?;
+    }
+    if let Some(genesis_header) = &snapshot.genesis_header {
+        batch_transaction.put(b"genesis_header", genesis_header)?;
+    }
+    db_lock.put_batch(batch_transaction)?;
+    db_lock.set_current_root(&header.state_root)?;
+
+    info!(
+        nexus_block = header.number,
+        state_root = ?header.state_root,
+        "Imported state snapshot"
+    );
+
+    Ok(header)
+}
+
+//Headers are linked back from the trusted block, so the header window the node
+//continues with cannot be altered in the file.
+fn check_header_store(header_store: &HeaderStore, header: &NexusHeader) -> Result<(), Error> {
+    match header_store.first() {
+        Some(i) if i.hash() == header.hash() => (),
+        _ => {
+            return Err(anyhow!(
+                "Last header of the header store is not the snapshot block."
+            ))
+        }
+    }
+
+    if header_store.max_size != header.header_window as usize
+        || header_store.inner().len() > header_store.max_size
+    {
+        return Err(anyhow!(
+            "Header store does not match header window of the chain."
+        ));
+    }
+
+    for (child, parent) in header_store
+        .inner()
+        .iter()
+        .zip(header_store.inner().iter().skip(1))
+    {
+        if child.parent_hash != parent.hash() || parent.number.checked_add(1) != Some(child.number)
+        {
+            return Err(anyhow!(
+                "Header {} of the header store does not link to its parent.",
+                child.number
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn save_snapshot(snapshot: &StateSnapshot, path: &str) -> Result<(), Error> {
+    fs::write(path, bincode::serialize(snapshot)?)
+        .await
+        .map_err(|e| anyhow!("Unable to write snapshot file {}: {}", path, e))
+}
+
+pub async fn load_snapshot(path: &str) -> Result<StateSnapshot, Error> {
+    let file_content = fs::read(path)
+        .await
+        .map_err(|e| anyhow!("Unable to read snapshot file {}: {}", path, e))?;
+
+    Ok(bincode::deserialize(&file_content)?)
+}
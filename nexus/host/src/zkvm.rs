@@ -0,0 +1,121 @@
+use anyhow::anyhow;
+use nexus_core::{
+    types::{Proof as NexusProof, ProofKind},
+    zkvm::traits::{ZKVMEnv, ZKVMProof, ZKVMProver},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug as DebugTrait;
+use std::str::FromStr;
+
+#[cfg(any(feature = "mock"))]
+use nexus_core::zkvm::mock::{MockProof, MockProver, MockZKVM};
+#[cfg(any(feature = "risc0"))]
+use nexus_core::zkvm::risczero::{RiscZeroProof, RiscZeroProver, ZKVM};
+#[cfg(any(feature = "sp1"))]
+use nexus_core::zkvm::sp1::{Sp1Proof, Sp1Prover, SP1ZKVM};
+#[cfg(any(feature = "risc0"))]
+use prover::NEXUS_RUNTIME_ELF;
+
+//Backends compiled into the binary, selected at startup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZKVMBackend {
+    #[cfg(any(feature = "risc0"))]
+    #[default]
+    Risc0,
+    #[cfg(any(feature = "sp1"))]
+    #[cfg_attr(not(feature = "risc0"), default)]
+    Sp1,
+    //Runs the runtime natively without proving, for tests.
+    #[cfg(any(feature = "mock"))]
+    #[cfg_attr(not(any(feature = "risc0", feature = "sp1")), default)]
+    Mock,
+}
+
+impl FromStr for ZKVMBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            #[cfg(any(feature = "risc0"))]
+            "risc0" => Ok(ZKVMBackend::Risc0),
+            #[cfg(any(feature = "sp1"))]
+            "sp1" => Ok(ZKVMBackend::Sp1),
+            #[cfg(any(feature = "mock"))]
+            "mock" => Ok(ZKVMBackend::Mock),
+            _ => Err(anyhow!(
+                "ZKVM backend {} is unknown or not compiled into this binary.",
+                s
+            )),
+        }
+    }
+}
+
+impl ZKVMBackend {
+    //Proof kinds accounts can use, as their proofs are verified by recursion in this backend.
+    pub fn supports_proof_kind(&self, kind: &ProofKind) -> bool {
+        match self {
+            #[cfg(any(feature = "risc0"))]
+            ZKVMBackend::Risc0 => ZKVM::supports_proof_kind(kind),
+            #[cfg(any(feature = "sp1"))]
+            ZKVMBackend::Sp1 => SP1ZKVM::supports_proof_kind(kind),
+            #[cfg(any(feature = "mock"))]
+            ZKVMBackend::Mock => MockZKVM::supports_proof_kind(kind),
+        }
+    }
+}
+
+//Ties the proof of a backend to its prover, guest environment and runtime ELF.
+pub trait BackendProof:
+    ZKVMProof
+    + Serialize
+    + Clone
+    + DebugTrait
+    + TryFrom<NexusProof, Error = anyhow::Error>
+    + Send
+    + Sync
+    + 'static
+{
+    type Prover: ZKVMProver<Self>;
+    type Env: ZKVMEnv + Send + Sync + 'static;
+
+    const BACKEND: ZKVMBackend;
+
+    fn elf() -> Vec<u8>;
+}
+
+#[cfg(any(feature = "risc0"))]
+impl BackendProof for RiscZeroProof {
+    type Prover = RiscZeroProver<'static>;
+    type Env = ZKVM;
+
+    const BACKEND: ZKVMBackend = ZKVMBackend::Risc0;
+
+    fn elf() -> Vec<u8> {
+        NEXUS_RUNTIME_ELF.to_vec()
+    }
+}
+
+#[cfg(any(feature = "sp1"))]
+impl BackendProof for Sp1Proof {
+    type Prover = Sp1Prover;
+    type Env = SP1ZKVM;
+
+    const BACKEND: ZKVMBackend = ZKVMBackend::Sp1;
+
+    fn elf() -> Vec<u8> {
+        include_bytes!("../../prover/sp1-guest/elf/riscv32im-succinct-zkvm-elf").to_vec()
+    }
+}
+
+#[cfg(any(feature = "mock"))]
+impl BackendProof for MockProof {
+    type Prover = MockProver;
+    type Env = MockZKVM;
+
+    const BACKEND: ZKVMBackend = ZKVMBackend::Mock;
+
+    //Only identifies the runtime, the mock prover executes it natively.
+    fn elf() -> Vec<u8> {
+        b"nexus_runtime_mock".to_vec()
+    }
+}
@@ -3,22 +3,28 @@ use avail_subxt::Header;
 use host::{run_nexus, setup_components};
 use mockall::predicate::*;
 use mockall::*;
-#[cfg(any(feature = "risc0"))]
+#[cfg(any(feature = "mock"))]
+use nexus_core::zkvm::mock::{MockProof as Proof, MockProver as Prover, MockZKVM as ZKVM};
+#[cfg(all(feature = "risc0", not(feature = "mock")))]
 use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
-#[cfg(any(feature = "sp1"))]
+#[cfg(all(feature = "sp1", not(any(feature = "risc0", feature = "mock"))))]
 use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
 use nexus_core::{
+    db::NodeDB,
     state_machine::StateMachine,
     types::{
         AccountState, AccountWithProof, AppAccountId, AppId, HeaderStore, InitAccount,
-        NexusBlockWithTransactions, StatementDigest, SubmitProof, Transaction, TransactionStatus,
-        TransactionWithStatus, TxParams, TxSignature, H256,
+        NexusBlockWithTransactions, NexusRollupPI, Proof as NexusProof, ProofKind, StatementDigest,
+        SubmitProof, Transaction, TransactionStatus, TransactionWithStatus, TxError, TxParams,
+        TxSignature, TxSigningKey, H256,
     },
-    zkvm::ProverMode,
+    utils::merkle::merkle_root,
+    zkvm::{traits::ZKVMEnv, ProverMode},
 };
 use nexus_core::{traits::NexusTransaction, types::NexusHeader};
 use relayer::Relayer;
 use reqwest::Client;
+use std::collections::HashMap;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
@@ -26,6 +32,130 @@ use tokio::sync::{watch, Mutex};
 use tokio::task;
 use tokio::time::{sleep, Duration};
 
+//Signing key of the transactions built by the tests.
+const TEST_SIGNING_KEY: TxSigningKey = TxSigningKey([1u8; 32]);
+//Statement of the test adapter account, whose proofs are built in the tests.
+const TEST_STATEMENT: StatementDigest = StatementDigest([
+    3963634887, 3768818894, 2608717727, 685163898, 341397292, 1233383743, 1619524616, 2323598105,
+]);
+
+fn test_app_account_id() -> AppAccountId {
+    AppAccountId::from(AppId(100))
+}
+
+fn init_account_tx(start_nexus_hash: H256, nonce: u64) -> Transaction {
+    Transaction::new_signed(
+        TxParams::InitAccount(InitAccount {
+            app_id: test_app_account_id(),
+            statement: TEST_STATEMENT,
+            statement_kind: ZKVM::proof_kind(),
+            start_nexus_hash,
+            owner: TEST_SIGNING_KEY.public_key(),
+        }),
+        nonce,
+        &TEST_SIGNING_KEY,
+    )
+}
+
+//Proof of the test adapter for the given public inputs, as the backend would
+//take it for recursion when not proving.
+#[cfg(any(feature = "mock"))]
+fn test_proof(public_inputs: &NexusRollupPI) -> NexusProof {
+    Proof {
+        image_id: public_inputs.img_id.0,
+        public_values: bincode::serialize(public_inputs).unwrap(),
+    }
+    .try_into()
+    .unwrap()
+}
+
+//Fake receipts are only accepted in dev mode, which the tests are run in.
+#[cfg(all(feature = "risc0", not(feature = "mock")))]
+fn test_proof(public_inputs: &NexusRollupPI) -> NexusProof {
+    use risc0_zkvm::{sha::Digest, FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+    let journal: Vec<u8> = risc0_zkvm::serde::to_vec(public_inputs)
+        .unwrap()
+        .into_iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    let claim = ReceiptClaim::ok(Digest::from(public_inputs.img_id.0), journal.clone());
+
+    Proof(Receipt::new(
+        InnerReceipt::Fake(FakeReceipt::new(claim)),
+        journal,
+    ))
+    .try_into()
+    .unwrap()
+}
+
+//Submit proof params of the test adapter at a rollup height, with a proof
+//anchored to the given nexus block.
+#[cfg(any(feature = "mock", feature = "risc0"))]
+fn submit_proof_params(start_nexus_hash: H256, nexus_hash: H256, height: u32) -> SubmitProof {
+    submit_proof_params_for(TEST_STATEMENT, start_nexus_hash, nexus_hash, height, None)
+}
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+fn submit_proof_params_for(
+    statement: StatementDigest,
+    start_nexus_hash: H256,
+    nexus_hash: H256,
+    height: u32,
+    message_root: Option<H256>,
+) -> SubmitProof {
+    let public_inputs = NexusRollupPI {
+        nexus_hash,
+        state_root: H256::from([height as u8; 32]),
+        height,
+        start_nexus_hash,
+        app_id: test_app_account_id(),
+        img_id: statement,
+        rollup_hash: Some(H256::from([height as u8 + 100; 32])),
+        message_root,
+        storage_hash: None,
+    };
+
+    SubmitProof {
+        proof: test_proof(&public_inputs),
+        nexus_hash: public_inputs.nexus_hash,
+        state_root: public_inputs.state_root,
+        height,
+        app_id: public_inputs.app_id,
+        data: public_inputs.rollup_hash,
+        message_root: public_inputs.message_root,
+        storage_hash: None,
+    }
+}
+
+async fn latest_nexus_header(node_db: &Arc<Mutex<NodeDB>>) -> NexusHeader {
+    node_db
+        .lock()
+        .await
+        .get::<HeaderStore>(b"previous_headers")
+        .expect("Unexpected internal db error")
+        .expect("Headers must now be committed")
+        .first()
+        .expect("Headers cannot be empty")
+        .clone()
+}
+
+async fn send_tx(port: u32, tx: &Transaction) {
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:{}/tx", port))
+        .json(tx)
+        .send()
+        .await
+        .unwrap();
+
+    if !response.status().is_success() {
+        panic!(
+            "Post transaction call failed with status code: {}",
+            response.status()
+        );
+    }
+}
+
 mock! {
   pub Relayer {}
 
@@ -411,40 +541,14 @@ async fn test_init_account_tx() {
         let shutdown_tx_clone = shutdown_tx.clone();
 
         Box::pin(async move {
-            #[cfg(any(feature = "risc0"))]
-            let tx_file_path = "tests/data/init_tx_risc0_1.json";
-
-            #[cfg(any(feature = "sp1"))]
-            let tx_file_path = "tests/data/init_tx_sp1.json";
-
             sender_in_box
                 .send(headers_in_box[0].clone())
                 .expect("Failed to send header in mock");
             //TODO: Keep the tests less complicated than below.
             tokio::time::sleep(Duration::from_secs(1)).await;
-            // Read and deserialize the transaction from the JSON file
-            let tx_json = fs::read_to_string(tx_file_path)
-                .await
-                .expect("Failed to read transaction JSON file");
-            let tx: Transaction =
-                serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-            let response = Client::new()
-                .post("http://127.0.0.1:7003/tx")
-                .json(&tx)
-                .send()
-                .await
-                .unwrap();
 
-            // Check if the request was successful
-            if response.status().is_success() {
-                ()
-            } else {
-                panic!(
-                    "Post transaction call failed with status code: {}",
-                    response.status()
-                );
-            }
+            let start_nexus_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            send_tx(7003, &init_account_tx(start_nexus_hash, 0)).await;
             println!("Sent second header");
             // Simulate sending headers
             sender_in_box
@@ -492,7 +596,7 @@ async fn test_init_account_tx() {
     };
 
     let (account_option, _) =
-        match state_lock.get_with_proof(&H256::from(app_account_id.0), current_version) {
+        match state_lock.get_with_proof(&app_account_id.account_key(), current_version) {
             Ok(i) => i,
             Err(e) => panic!("State call failed with error: {:?}", e),
         };
@@ -505,15 +609,22 @@ async fn test_init_account_tx() {
             last_proof_height: 0,
             start_nexus_hash: old_headers.inner().last().unwrap().hash().into(),
             state_root: [0u8; 32],
-            statement: StatementDigest([
-                3963634887, 3768818894, 2608717727, 685163898, 341397292, 1233383743, 1619524616,
-                2323598105
-            ]),
+            statement: TEST_STATEMENT,
+            owner: TEST_SIGNING_KEY.public_key(),
+            nonce: 1,
+            pending_statement: StatementDigest::zero(),
+            pending_statement_height: 0,
+            message_root: [0; 32],
+            rollup_hash: [0; 32],
+            storage_hash: [0; 32],
+            statement_kind: ZKVM::proof_kind(),
+            pending_statement_kind: ProofKind::Risc0Receipt,
+            generation: 0,
         })
     )
 }
 
-#[cfg(feature = "risc0")]
+#[cfg(any(feature = "mock", feature = "risc0"))]
 #[tokio::test]
 async fn test_update_tx() {
     use serde_json;
@@ -568,10 +679,7 @@ async fn test_update_tx() {
         let shutdown_tx_clone = shutdown_tx.clone();
 
         Box::pin(async move {
-            let init_tx_path = "tests/data/init_tx_risc0_1.json";
-            fn submit_proof_tx_path(n: usize) -> String {
-                format!("tests/data/submitproof_tx_risc0_{}.json", n)
-            }
+            let mut start_nexus_hash = H256::zero();
             for n in 0..10 {
                 sender_in_box
                     .send(headers_in_box[n].clone())
@@ -579,42 +687,24 @@ async fn test_update_tx() {
                 //TODO: Keep the tests less complicated than below.
                 tokio::time::sleep(Duration::from_secs(1)).await;
 
+                //Each proof is anchored to the block built before it is sent.
+                let latest_hash = latest_nexus_header(&node_db_in_box).await.hash();
                 let tx = if n == 0 {
-                    // Read and deserialize the transaction from the JSON file
-                    let tx_json = fs::read_to_string(init_tx_path)
-                        .await
-                        .expect("Failed to read transaction JSON file");
-                    let tx: Transaction =
-                        serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-                    tx
+                    start_nexus_hash = latest_hash;
+                    init_account_tx(start_nexus_hash.clone(), 0)
                 } else {
-                    // Read and deserialize the transaction from the JSON file
-                    let tx_json = fs::read_to_string(submit_proof_tx_path(n))
-                        .await
-                        .expect("Failed to read transaction JSON file");
-                    let mut tx: Transaction =
-                        serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-                    tx
+                    Transaction::new_signed(
+                        TxParams::SubmitProof(submit_proof_params(
+                            start_nexus_hash.clone(),
+                            latest_hash,
+                            n as u32,
+                        )),
+                        n as u64,
+                        &TEST_SIGNING_KEY,
+                    )
                 };
 
-                let response = Client::new()
-                    .post("http://127.0.0.1:7004/tx")
-                    .json(&tx)
-                    .send()
-                    .await
-                    .unwrap();
-
-                // Check if the request was successful
-                if response.status().is_success() {
-                    ()
-                } else {
-                    panic!(
-                        "Post transaction call failed with status code: {}",
-                        response.status()
-                    );
-                }
+                send_tx(7004, &tx).await;
             }
 
             println!("Sent second header");
@@ -667,7 +757,7 @@ async fn test_update_tx() {
     };
 
     let (account_option, _) =
-        match state_lock.get_with_proof(&H256::from(app_account_id.0), current_version) {
+        match state_lock.get_with_proof(&app_account_id.account_key(), current_version) {
             Ok(i) => i,
             Err(e) => panic!("State call failed with error: {:?}", e),
         };
@@ -679,14 +769,18 @@ async fn test_update_tx() {
             height: 9,
             last_proof_height: 9,
             start_nexus_hash: old_headers.inner().last().unwrap().hash().into(),
-            state_root: [
-                6, 216, 214, 89, 28, 81, 211, 26, 3, 85, 59, 232, 9, 185, 9, 104, 182, 224, 25,
-                245, 45, 166, 90, 253, 133, 157, 64, 140, 201, 186, 98, 21
-            ],
-            statement: StatementDigest([
-                3963634887, 3768818894, 2608717727, 685163898, 341397292, 1233383743, 1619524616,
-                2323598105
-            ]),
+            state_root: [9; 32],
+            statement: TEST_STATEMENT,
+            owner: TEST_SIGNING_KEY.public_key(),
+            nonce: 10,
+            pending_statement: StatementDigest::zero(),
+            pending_statement_height: 0,
+            message_root: [0; 32],
+            rollup_hash: [109; 32],
+            storage_hash: [0; 32],
+            statement_kind: ZKVM::proof_kind(),
+            pending_statement_kind: ProofKind::Risc0Receipt,
+            generation: 0,
         })
     )
 }
@@ -745,41 +839,14 @@ async fn test_transaction_status() {
         let shutdown_tx_clone = shutdown_tx.clone();
 
         Box::pin(async move {
-            #[cfg(any(feature = "risc0"))]
-            let tx_file_path = "tests/data/init_tx_risc0_1.json";
-
-            #[cfg(any(feature = "sp1"))]
-            let tx_file_path = "tests/data/init_tx_sp1.json";
-
             sender_in_box
                 .send(headers_in_box[0].clone())
                 .expect("Failed to send header in mock");
             //TODO: Keep the tests less complicated than below.
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            // Read and deserialize the transaction from the JSON file
-            let tx_json = fs::read_to_string(tx_file_path)
-                .await
-                .expect("Failed to read transaction JSON file");
-            let tx: Transaction =
-                serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-            let response = Client::new()
-                .post("http://127.0.0.1:7005/tx")
-                .json(&tx)
-                .send()
-                .await
-                .unwrap();
-
-            // Check if the request was successful
-            if response.status().is_success() {
-                ()
-            } else {
-                panic!(
-                    "Post transaction call failed with status code: {}",
-                    response.status()
-                );
-            }
+            let tx = init_account_tx(latest_nexus_header(&node_db_in_box).await.hash(), 0);
+            send_tx(7005, &tx).await;
             println!("Sent second header");
             // Simulate sending headers
             sender_in_box
@@ -787,15 +854,7 @@ async fn test_transaction_status() {
                 .expect("Failed to send header in mock");
             tokio::time::sleep(Duration::from_secs(2)).await;
 
-            let latest_block: NexusHeader = node_db_in_box
-                .lock()
-                .await
-                .get::<HeaderStore>(b"previous_headers")
-                .expect("Unexpected internal db error")
-                .expect("Headers must now be committed")
-                .first()
-                .expect("Headers cannot be empty")
-                .clone();
+            let latest_block: NexusHeader = latest_nexus_header(&node_db_in_box).await;
             let response = Client::new()
                 .get(format!(
                     "http://127.0.0.1:7005/tx_status?tx_hash={}",
@@ -812,7 +871,8 @@ async fn test_transaction_status() {
                 TransactionWithStatus {
                     transaction: tx,
                     status: TransactionStatus::Successful,
-                    block_hash: Some(latest_block.hash())
+                    block_hash: Some(latest_block.hash()),
+                    error: None,
                 }
             );
 
@@ -838,6 +898,7 @@ async fn test_transaction_status() {
     };
 }
 
+#[cfg(any(feature = "mock", feature = "risc0"))]
 #[tokio::test]
 async fn test_get_state_api() {
     use serde_json;
@@ -892,16 +953,8 @@ async fn test_get_state_api() {
         let shutdown_tx_clone = shutdown_tx.clone();
 
         Box::pin(async move {
-            fn submit_proof_tx_path(n: usize) -> String {
-                format!("tests/data/submitproof_tx_risc0_{}.json", n)
-            }
-            #[cfg(any(feature = "risc0"))]
-            let init_tx_path = "tests/data/init_tx_risc0_1.json";
-
-            #[cfg(any(feature = "sp1"))]
-            let init_tx_path = "tests/data/init_tx_sp1.json";
-
             let mut tx_hashes: Vec<String> = vec![];
+            let mut start_nexus_hash = H256::zero();
 
             for n in 0..3 {
                 sender_in_box
@@ -910,33 +963,24 @@ async fn test_get_state_api() {
                 //TODO: Keep the tests less complicated than below.
                 tokio::time::sleep(Duration::from_secs(1)).await;
 
+                let latest_hash = latest_nexus_header(&node_db_in_box).await.hash();
                 let tx = if n == 0 {
-                    // Read and deserialize the transaction from the JSON file
-                    let tx_json = fs::read_to_string(init_tx_path)
-                        .await
-                        .expect("Failed to read transaction JSON file");
-                    let tx: Transaction =
-                        serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-                    tx
+                    start_nexus_hash = latest_hash;
+                    init_account_tx(start_nexus_hash.clone(), 0)
                 } else {
-                    // Read and deserialize the transaction from the JSON file
-                    let tx_json = fs::read_to_string(submit_proof_tx_path(n))
-                        .await
-                        .expect("Failed to read transaction JSON file");
-                    let mut tx: Transaction =
-                        serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-                    tx
+                    Transaction::new_signed(
+                        TxParams::SubmitProof(submit_proof_params(
+                            start_nexus_hash.clone(),
+                            latest_hash,
+                            n as u32,
+                        )),
+                        n as u64,
+                        &TEST_SIGNING_KEY,
+                    )
                 };
 
                 tx_hashes.push(hex::encode(tx.hash().as_slice()));
-                let response = Client::new()
-                    .post("http://127.0.0.1:7006/tx")
-                    .json(&tx)
-                    .send()
-                    .await
-                    .unwrap();
+                send_tx(7006, &tx).await;
             }
 
             // Simulate sending headers
@@ -975,15 +1019,19 @@ async fn test_get_state_api() {
                 AccountState {
                     height: 0,
                     last_proof_height: 0,
-                    start_nexus_hash: [
-                        124, 155, 177, 24, 187, 203, 222, 53, 134, 69, 91, 202, 176, 57, 205, 125,
-                        6, 190, 127, 189, 221, 197, 246, 121, 254, 142, 231, 94, 10, 210, 115, 246
-                    ],
+                    start_nexus_hash: start_nexus_hash.clone().into(),
                     state_root: [0u8; 32],
-                    statement: StatementDigest([
-                        3963634887, 3768818894, 2608717727, 685163898, 341397292, 1233383743,
-                        1619524616, 2323598105
-                    ]),
+                    statement: TEST_STATEMENT,
+                    owner: TEST_SIGNING_KEY.public_key(),
+                    nonce: 1,
+                    pending_statement: StatementDigest::zero(),
+                    pending_statement_height: 0,
+                    message_root: [0; 32],
+                    rollup_hash: [0; 32],
+                    storage_hash: [0; 32],
+                    statement_kind: ZKVM::proof_kind(),
+                    pending_statement_kind: ProofKind::Risc0Receipt,
+                    generation: 0,
                 }
             );
 
@@ -996,8 +1044,6 @@ async fn test_get_state_api() {
                 .await
                 .unwrap();
 
-            //println!("response: {:?}", response.text().await.unwrap());
-
             let tx_status: TransactionWithStatus = response.json().await.unwrap();
             let app_account_id = AppAccountId::from(AppId(100));
 
@@ -1023,18 +1069,19 @@ async fn test_get_state_api() {
                 AccountState {
                     height: 1,
                     last_proof_height: 1,
-                    start_nexus_hash: [
-                        124, 155, 177, 24, 187, 203, 222, 53, 134, 69, 91, 202, 176, 57, 205, 125,
-                        6, 190, 127, 189, 221, 197, 246, 121, 254, 142, 231, 94, 10, 210, 115, 246
-                    ],
-                    state_root: [
-                        106, 253, 110, 223, 62, 221, 125, 87, 216, 204, 244, 156, 83, 209, 14, 63,
-                        0, 95, 50, 234, 154, 7, 99, 193, 144, 166, 121, 106, 221, 81, 90, 138
-                    ],
-                    statement: StatementDigest([
-                        3963634887, 3768818894, 2608717727, 685163898, 341397292, 1233383743,
-                        1619524616, 2323598105
-                    ]),
+                    start_nexus_hash: start_nexus_hash.into(),
+                    state_root: [1; 32],
+                    statement: TEST_STATEMENT,
+                    owner: TEST_SIGNING_KEY.public_key(),
+                    nonce: 2,
+                    pending_statement: StatementDigest::zero(),
+                    pending_statement_height: 0,
+                    message_root: [0; 32],
+                    rollup_hash: [101; 32],
+                    storage_hash: [0; 32],
+                    statement_kind: ZKVM::proof_kind(),
+                    pending_statement_kind: ProofKind::Risc0Receipt,
+                    generation: 0,
                 }
             );
             shutdown_tx_clone.send(true).unwrap();
@@ -1101,6 +1148,7 @@ async fn test_get_block_api() {
     let prover_mode = ProverMode::MockProof;
     let (node_db, state) = setup_components(db_path);
     let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
     let state_clone = state.clone();
     let mut state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
@@ -1108,44 +1156,19 @@ async fn test_get_block_api() {
     mock_relayer.expect_start().returning(move |_| {
         let headers_in_box = headers_clone.clone();
         let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
         let shutdown_tx_clone = shutdown_tx.clone();
 
         Box::pin(async move {
-            #[cfg(any(feature = "risc0"))]
-            let tx_file_path = "tests/data/init_tx_risc0_1.json";
-
-            #[cfg(any(feature = "sp1"))]
-            let tx_file_path = "tests/data/init_tx_sp1.json";
-
             sender_in_box
                 .send(headers_in_box[0].clone())
                 .expect("Failed to send header in mock");
             //TODO: Keep the tests less complicated than below.
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            // Read and deserialize the transaction from the JSON file
-            let tx_json = fs::read_to_string(tx_file_path)
-                .await
-                .expect("Failed to read transaction JSON file");
-            let tx: Transaction =
-                serde_json::from_str(&tx_json).expect("Failed to parse transaction JSON");
-
-            let response = Client::new()
-                .post("http://127.0.0.1:7007/tx")
-                .json(&tx)
-                .send()
-                .await
-                .unwrap();
-
-            // Check if the request was successful
-            if response.status().is_success() {
-                ()
-            } else {
-                panic!(
-                    "Post transaction call failed with status code: {}",
-                    response.status()
-                );
-            }
+            let parent_header = latest_nexus_header(&node_db_in_box).await;
+            let tx = init_account_tx(parent_header.hash(), 0);
+            send_tx(7007, &tx).await;
             println!("Sent second header");
             // Simulate sending headers
             sender_in_box
@@ -1165,14 +1188,21 @@ async fn test_get_block_api() {
                 .await
                 .expect("Response with Nexus block not encoded as expected.");
 
-            let json_path = "tests/data/nexus_header_risc0_1.json";
-            let file_content = fs::read_to_string(json_path)
-                .await
-                .expect("Failed to read nexus header json file.");
-            let expected_header: NexusBlockWithTransactions =
-                serde_json::from_str(&file_content).expect("Failed to parse Nexus header file.");
-
-            assert_eq!(expected_header, block_with_txs);
+            let header = latest_nexus_header(&node_db_in_box).await;
+            assert_eq!(block_with_txs.header, header);
+            assert_eq!(header.number, parent_header.number + 1);
+            assert_eq!(header.parent_hash, parent_header.hash());
+            assert_eq!(header.prev_state_root, parent_header.state_root);
+            assert_eq!(header.tx_root, merkle_root(&[tx.hash()]));
+            assert_eq!(
+                block_with_txs.transactions,
+                vec![TransactionWithStatus {
+                    transaction: tx,
+                    status: TransactionStatus::Successful,
+                    block_hash: Some(header.hash()),
+                    error: None,
+                }]
+            );
 
             shutdown_tx_clone.send(true).unwrap();
         })
@@ -1195,3 +1225,2287 @@ async fn test_get_block_api() {
         }
     };
 }
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+#[tokio::test]
+async fn test_stale_proofs() {
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_stale_proofs";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    // Mock the Relayer instance
+    let mut mock_relayer = MockRelayer::new();
+
+    // Set up an unbounded channel to simulate sending and receiving headers
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+
+    // Mock `receiver` to return the receiver end of the channel
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    // Read headers from the JSON file
+    let json_path = "tests/data/avail_headers.json";
+    let file_content = fs::read_to_string(json_path)
+        .await
+        .expect("Failed to read headers JSON file");
+    let mut headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::MockProof;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let mut state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            let mut start_nexus_hash = H256::zero();
+            let mut anchors: Vec<H256> = vec![];
+            let mut stale_txs: Vec<(Transaction, TxError)> = vec![];
+
+            //Valid history, account ends at rollup height 2 with nonce 3. Each tx
+            //is sent after the block it is anchored to is built.
+            for n in 0..6 {
+                sender_in_box
+                    .send(headers_in_box[n].clone())
+                    .expect("Failed to send header in mock");
+                //TODO: Keep the tests less complicated than below.
+                tokio::time::sleep(Duration::from_secs(1)).await;
+
+                anchors.push(latest_nexus_header(&node_db_in_box).await.hash());
+                if n == 0 {
+                    start_nexus_hash = anchors[0].clone();
+                }
+                let proof_tx = |anchor: usize, height: u32, nonce: u64| {
+                    Transaction::new_signed(
+                        TxParams::SubmitProof(submit_proof_params(
+                            start_nexus_hash.clone(),
+                            anchors[anchor].clone(),
+                            height,
+                        )),
+                        nonce,
+                        &TEST_SIGNING_KEY,
+                    )
+                };
+
+                let tx = match n {
+                    0 => init_account_tx(start_nexus_hash.clone(), 0),
+                    1 | 2 => proof_tx(n, n as u32, n as u64),
+                    //Same proof as the last accepted one, with a fresh nonce.
+                    3 => {
+                        let tx = proof_tx(2, 2, 3);
+                        stale_txs
+                            .push((tx.clone(), TxError::StaleRollupHeight { current_height: 2 }));
+                        tx
+                    }
+                    //Proof for a rollup height older than the account's.
+                    4 => {
+                        let tx = proof_tx(1, 1, 3);
+                        stale_txs
+                            .push((tx.clone(), TxError::StaleRollupHeight { current_height: 2 }));
+                        tx
+                    }
+                    //Higher rollup height, but anchored to the same nexus block as the last proof.
+                    _ => {
+                        let tx = proof_tx(2, 3, 3);
+                        stale_txs.push((
+                            tx.clone(),
+                            TxError::StaleAnchor {
+                                last_proof_height: 2,
+                            },
+                        ));
+                        tx
+                    }
+                };
+
+                send_tx(7008, &tx).await;
+            }
+
+            sender_in_box
+                .send(headers_in_box[6].clone())
+                .expect("Failed to send header in mock");
+            //TODO: Keep the tests less complicated than below.
+            tokio::time::sleep(Duration::from_secs(3)).await;
+
+            let app_account_id = AppAccountId::from(AppId(100));
+
+            for (tx, expected_error) in stale_txs.iter() {
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7008/tx_status?tx_hash={}",
+                        hex::encode(tx.hash().as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+
+                let tx_status: TransactionWithStatus = response.json().await.unwrap();
+
+                assert_eq!(tx_status.status, TransactionStatus::Failed);
+                assert_eq!(tx_status.error.as_ref(), Some(expected_error));
+
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7008/account?app_account_id={}&block_hash={}",
+                        hex::encode(app_account_id.0),
+                        hex::encode(tx_status.block_hash.unwrap().as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+
+                let account_with_proof: AccountWithProof =
+                    response.json().await.expect("API call to nexus failed");
+
+                assert_eq!(account_with_proof.account.height, 2);
+                assert_eq!(account_with_proof.account.last_proof_height, 2);
+                assert_eq!(account_with_proof.account.nonce, 3);
+            }
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    // Spawn the main Nexus logic
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7008),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+#[tokio::test]
+async fn test_invalid_proof() {
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_invalid_proof";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    // Mock the Relayer instance
+    let mut mock_relayer = MockRelayer::new();
+
+    // Set up an unbounded channel to simulate sending and receiving headers
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+
+    // Mock `receiver` to return the receiver end of the channel
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    // Read headers from the JSON file
+    let json_path = "tests/data/avail_headers.json";
+    let file_content = fs::read_to_string(json_path)
+        .await
+        .expect("Failed to read headers JSON file");
+    let mut headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::MockProof;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let mut state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            //TODO: Keep the tests less complicated than below.
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let start_nexus_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            send_tx(7009, &init_account_tx(start_nexus_hash.clone(), 0)).await;
+
+            sender_in_box
+                .send(headers_in_box[1].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let anchor = latest_nexus_header(&node_db_in_box).await.hash();
+            let valid_params = submit_proof_params(start_nexus_hash.clone(), anchor.clone(), 1);
+
+            //Proof that can not be decoded by the backend.
+            let mut undecodable_params = valid_params.clone();
+            undecodable_params.proof = NexusProof {
+                kind: ZKVM::proof_kind(),
+                data: vec![0],
+            };
+            //Valid proof, submitted with a state root it does not commit to.
+            let mut mismatched_params = valid_params.clone();
+            mismatched_params.state_root = H256::from([42u8; 32]);
+
+            let mut invalid_txs: Vec<Transaction> = vec![];
+            for (n, params) in [undecodable_params, mismatched_params]
+                .into_iter()
+                .enumerate()
+            {
+                let tx =
+                    Transaction::new_signed(TxParams::SubmitProof(params), 1, &TEST_SIGNING_KEY);
+                send_tx(7009, &tx).await;
+                invalid_txs.push(tx);
+
+                sender_in_box
+                    .send(headers_in_box[n + 2].clone())
+                    .expect("Failed to send header in mock");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            //Node keeps running, and accepts a valid proof with the same nonce.
+            let valid_tx =
+                Transaction::new_signed(TxParams::SubmitProof(valid_params), 1, &TEST_SIGNING_KEY);
+            send_tx(7009, &valid_tx).await;
+            sender_in_box
+                .send(headers_in_box[4].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            for tx in invalid_txs.iter() {
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7009/tx_status?tx_hash={}",
+                        hex::encode(tx.hash().as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+
+                let tx_status: TransactionWithStatus = response.json().await.unwrap();
+
+                assert_eq!(tx_status.status, TransactionStatus::Failed);
+                assert_eq!(tx_status.error, Some(TxError::InvalidProof));
+            }
+
+            let response = Client::new()
+                .get(format!(
+                    "http://127.0.0.1:7009/tx_status?tx_hash={}",
+                    hex::encode(valid_tx.hash().as_slice())
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            let tx_status: TransactionWithStatus = response.json().await.unwrap();
+            assert_eq!(tx_status.status, TransactionStatus::Successful);
+
+            let response = Client::new()
+                .get(format!(
+                    "http://127.0.0.1:7009/account?app_account_id={}&block_hash={}",
+                    hex::encode(test_app_account_id().0),
+                    hex::encode(tx_status.block_hash.unwrap().as_slice())
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            let account_with_proof: AccountWithProof =
+                response.json().await.expect("API call to nexus failed");
+
+            assert_eq!(account_with_proof.account.height, 1);
+            assert_eq!(account_with_proof.account.state_root, [1u8; 32]);
+            assert_eq!(account_with_proof.account.nonce, 2);
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    // Spawn the main Nexus logic
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7009),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+#[tokio::test]
+async fn test_statement_rotation() {
+    use nexus_core::types::RotateStatement;
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_statement_rotation";
+    //Statement of the upgraded adapter program.
+    const ROTATED_STATEMENT: StatementDigest = StatementDigest([1, 2, 3, 4, 5, 6, 7, 8]);
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    let mut mock_relayer = MockRelayer::new();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    let file_content = fs::read_to_string("tests/data/avail_headers.json")
+        .await
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::MockProof;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            let tx_status = |tx: Transaction| async move {
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7010/tx_status?tx_hash={}",
+                        hex::encode(tx.hash().as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+
+                let tx_status: TransactionWithStatus = response.json().await.unwrap();
+                tx_status
+            };
+
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let start_nexus_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            send_tx(7010, &init_account_tx(start_nexus_hash.clone(), 0)).await;
+
+            sender_in_box
+                .send(headers_in_box[1].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            //Rotation takes effect two nexus blocks after the current one.
+            let old_anchor = latest_nexus_header(&node_db_in_box).await;
+            let effective_height = old_anchor.number + 2;
+            let rotate_tx = Transaction::new_signed(
+                TxParams::RotateStatement(RotateStatement {
+                    app_id: test_app_account_id(),
+                    statement: ROTATED_STATEMENT,
+                    statement_kind: ZKVM::proof_kind(),
+                    effective_height: Some(effective_height),
+                }),
+                1,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7010, &rotate_tx).await;
+            sender_in_box
+                .send(headers_in_box[2].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            //Proof anchored before the rotation is still against the old statement.
+            let old_tx = Transaction::new_signed(
+                TxParams::SubmitProof(submit_proof_params(
+                    start_nexus_hash.clone(),
+                    old_anchor.hash(),
+                    1,
+                )),
+                2,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7010, &old_tx).await;
+            sender_in_box
+                .send(headers_in_box[3].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let new_anchor = latest_nexus_header(&node_db_in_box).await;
+            assert!(new_anchor.number >= effective_height);
+
+            //Proofs anchored after the rotation have to be against the new statement.
+            let stale_statement_tx = Transaction::new_signed(
+                TxParams::SubmitProof(submit_proof_params(
+                    start_nexus_hash.clone(),
+                    new_anchor.hash(),
+                    2,
+                )),
+                3,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7010, &stale_statement_tx).await;
+            sender_in_box
+                .send(headers_in_box[4].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let new_tx = Transaction::new_signed(
+                TxParams::SubmitProof(submit_proof_params_for(
+                    ROTATED_STATEMENT,
+                    start_nexus_hash.clone(),
+                    new_anchor.hash(),
+                    2,
+                    None,
+                )),
+                3,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7010, &new_tx).await;
+            sender_in_box
+                .send(headers_in_box[5].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            for tx in [rotate_tx, old_tx] {
+                assert_eq!(tx_status(tx).await.status, TransactionStatus::Successful);
+            }
+
+            let status = tx_status(stale_statement_tx).await;
+            assert_eq!(status.status, TransactionStatus::Failed);
+            assert_eq!(status.error, Some(TxError::InvalidProof));
+
+            let status = tx_status(new_tx).await;
+            assert_eq!(status.status, TransactionStatus::Successful);
+
+            let response = Client::new()
+                .get(format!(
+                    "http://127.0.0.1:7010/account?app_account_id={}&block_hash={}",
+                    hex::encode(test_app_account_id().0),
+                    hex::encode(status.block_hash.unwrap().as_slice())
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            let account_with_proof: AccountWithProof =
+                response.json().await.expect("API call to nexus failed");
+
+            assert_eq!(account_with_proof.account.statement, ROTATED_STATEMENT);
+            assert_eq!(
+                account_with_proof.account.pending_statement,
+                StatementDigest::zero()
+            );
+            assert_eq!(account_with_proof.account.height, 2);
+            assert_eq!(account_with_proof.account.state_root, [2u8; 32]);
+            assert_eq!(account_with_proof.account.nonce, 4);
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7010),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+#[tokio::test]
+async fn test_message_root() {
+    use nexus_core::types::MessageRootProof;
+    use nexus_core::utils::merkle::verify_merkle_proof;
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_message_root";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    let mut mock_relayer = MockRelayer::new();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    let file_content = fs::read_to_string("tests/data/avail_headers.json")
+        .await
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::MockProof;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            let message_proof = |block_hash: H256| async move {
+                Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7011/message_proof?app_account_id={}&block_hash={}",
+                        hex::encode(test_app_account_id().0),
+                        hex::encode(block_hash.as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap()
+            };
+
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let start_nexus_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            send_tx(7011, &init_account_tx(start_nexus_hash.clone(), 0)).await;
+
+            for header in headers_in_box[1..3].iter() {
+                sender_in_box
+                    .send(header.clone())
+                    .expect("Failed to send header in mock");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            //Block with no proofs has no messages.
+            let first_anchor = latest_nexus_header(&node_db_in_box).await;
+            assert_eq!(first_anchor.message_root, H256::zero());
+            assert_eq!(
+                message_proof(first_anchor.hash()).await.status(),
+                reqwest::StatusCode::NOT_FOUND
+            );
+
+            sender_in_box
+                .send(headers_in_box[3].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let second_anchor = latest_nexus_header(&node_db_in_box).await;
+
+            //Both proofs of the account export messages in the same block.
+            let exporting_params: Vec<SubmitProof> = vec![
+                submit_proof_params_for(
+                    TEST_STATEMENT,
+                    start_nexus_hash.clone(),
+                    first_anchor.hash(),
+                    1,
+                    Some(H256::from([7u8; 32])),
+                ),
+                submit_proof_params_for(
+                    TEST_STATEMENT,
+                    start_nexus_hash.clone(),
+                    second_anchor.hash(),
+                    2,
+                    Some(H256::from([8u8; 32])),
+                ),
+            ];
+            for (n, params) in exporting_params.iter().enumerate() {
+                let tx = Transaction::new_signed(
+                    TxParams::SubmitProof(params.clone()),
+                    n as u64 + 1,
+                    &TEST_SIGNING_KEY,
+                );
+                send_tx(7011, &tx).await;
+            }
+            sender_in_box
+                .send(headers_in_box[4].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            let header = latest_nexus_header(&node_db_in_box).await;
+            let message_leaves: Vec<H256> = exporting_params
+                .iter()
+                .map(|params| params.message_leaf().unwrap())
+                .collect();
+            assert_eq!(header.message_root, merkle_root(&message_leaves));
+
+            let response = message_proof(header.hash()).await;
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+            let proof: MessageRootProof = response.json().await.unwrap();
+
+            //Proof is for the message root in the account state, from the last proof.
+            assert_eq!(proof.app_id, test_app_account_id());
+            assert_eq!(proof.account_message_root, H256::from([8u8; 32]));
+            assert_eq!(proof.block_hash, header.hash());
+            assert_eq!(proof.message_root, header.message_root);
+            assert_eq!(proof.index, 1);
+            assert_eq!(proof.leaf_count, 2);
+            verify_merkle_proof(
+                &message_leaves[1],
+                proof.index as usize,
+                proof.leaf_count as usize,
+                &proof.proof,
+                &proof.message_root,
+            )
+            .expect("Message root proof does not verify");
+
+            let response = Client::new()
+                .get(format!(
+                    "http://127.0.0.1:7011/account?app_account_id={}&block_hash={}",
+                    hex::encode(test_app_account_id().0),
+                    hex::encode(header.hash().as_slice())
+                ))
+                .send()
+                .await
+                .unwrap();
+
+            let account_with_proof: AccountWithProof =
+                response.json().await.expect("API call to nexus failed");
+            assert_eq!(account_with_proof.account.message_root, [8u8; 32]);
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7011),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[tokio::test]
+async fn test_close_account() {
+    use nexus_core::types::{AccountStatus, CloseAccount};
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_close_account";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    let mut mock_relayer = MockRelayer::new();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    let file_content = fs::read_to_string("tests/data/avail_headers.json")
+        .await
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::MockProof;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            let get_account = |block_hash: H256| async move {
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7012/account?app_account_id={}&block_hash={}",
+                        hex::encode(test_app_account_id().0),
+                        hex::encode(block_hash.as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+
+                let account_with_proof: AccountWithProof =
+                    response.json().await.expect("API call to nexus failed");
+                account_with_proof
+            };
+
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let genesis_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            assert_eq!(
+                get_account(genesis_hash).await.status,
+                AccountStatus::NotFound
+            );
+
+            send_tx(7012, &init_account_tx(genesis_hash.clone(), 0)).await;
+            sender_in_box
+                .send(headers_in_box[1].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let init_hash = latest_nexus_header(&node_db_in_box).await.hash();
+            assert_eq!(get_account(init_hash).await.status, AccountStatus::Active);
+
+            let close_tx = Transaction::new_signed(
+                TxParams::CloseAccount(CloseAccount {
+                    app_id: test_app_account_id(),
+                }),
+                1,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7012, &close_tx).await;
+            sender_in_box
+                .send(headers_in_box[2].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let response = Client::new()
+                .get(format!(
+                    "http://127.0.0.1:7012/tx_status?tx_hash={}",
+                    hex::encode(close_tx.hash().as_slice())
+                ))
+                .send()
+                .await
+                .unwrap();
+            let tx_status: TransactionWithStatus = response.json().await.unwrap();
+            assert_eq!(tx_status.status, TransactionStatus::Successful);
+
+            //Closed leaf stays in the tree with the nonce of the account.
+            let account_with_proof = get_account(tx_status.block_hash.unwrap()).await;
+            assert_eq!(account_with_proof.status, AccountStatus::Closed);
+            assert!(!account_with_proof.account.is_initiated());
+            assert_eq!(account_with_proof.account.nonce, 2);
+            assert_eq!(account_with_proof.account.generation, 1);
+
+            //Account is registered again with the next nonce.
+            let reinit_tx = init_account_tx(genesis_hash.clone(), 2);
+            send_tx(7012, &reinit_tx).await;
+            sender_in_box
+                .send(headers_in_box[3].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            let account_with_proof =
+                get_account(latest_nexus_header(&node_db_in_box).await.hash()).await;
+            assert_eq!(account_with_proof.status, AccountStatus::Active);
+            assert_eq!(account_with_proof.account.nonce, 3);
+            assert_eq!(account_with_proof.account.generation, 1);
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7012),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[test]
+fn test_proof_kind_codes() {
+    use parity_scale_codec::Encode;
+
+    //Kinds in txs are scale and bincode encoded, and by code in the account state.
+    for kind in [
+        ProofKind::Risc0Receipt,
+        ProofKind::Sp1Compressed,
+        ProofKind::Mock,
+    ] {
+        assert_eq!(Encode::encode(&kind), vec![kind.code()]);
+        assert_eq!(
+            bincode::serialize(&kind).unwrap(),
+            (kind.code() as u32).to_le_bytes().to_vec()
+        );
+        assert_eq!(ProofKind::from_code(kind.code()), Some(kind));
+    }
+    assert_eq!(ProofKind::from_code(3), None);
+}
+
+#[tokio::test]
+async fn test_account_state_encoding_vector() {
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_account_state_encoding_vector";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    //Same vector is used by the contract tests, to keep the on chain layout in sync.
+    let vector_path = "../../contracts/test/data/account_state_encoding.json";
+    let file_content = fs::read_to_string(vector_path)
+        .await
+        .expect("Failed to read account state encoding vector");
+    let vector: serde_json::Value =
+        serde_json::from_str(&file_content).expect("Failed to parse account state encoding vector");
+
+    fn bytes32(value: &serde_json::Value) -> [u8; 32] {
+        hex::decode(value.as_str().unwrap().trim_start_matches("0x"))
+            .expect("Invalid hex in vector")
+            .try_into()
+            .expect("Expected 32 bytes in vector")
+    }
+
+    fn statement(value: &serde_json::Value) -> StatementDigest {
+        let mut digest = [0u32; 8];
+        for (i, chunk) in bytes32(value).chunks(4).enumerate() {
+            digest[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        StatementDigest(digest)
+    }
+
+    let account = &vector["account"];
+    let account_state = AccountState {
+        statement: statement(&account["statement"]),
+        state_root: bytes32(&account["state_root"]),
+        start_nexus_hash: bytes32(&account["start_nexus_hash"]),
+        last_proof_height: account["last_proof_height"].as_u64().unwrap() as u32,
+        height: account["height"].as_u64().unwrap() as u32,
+        owner: bytes32(&account["owner"]),
+        nonce: account["nonce"].as_u64().unwrap(),
+        pending_statement: statement(&account["pending_statement"]),
+        pending_statement_height: account["pending_statement_height"].as_u64().unwrap() as u32,
+        message_root: bytes32(&account["message_root"]),
+        rollup_hash: bytes32(&account["rollup_hash"]),
+        storage_hash: bytes32(&account["storage_hash"]),
+        statement_kind: ProofKind::from_code(account["statement_kind"].as_u64().unwrap() as u8)
+            .unwrap(),
+        pending_statement_kind: ProofKind::from_code(
+            account["pending_statement_kind"].as_u64().unwrap() as u8,
+        )
+        .unwrap(),
+        generation: account["generation"].as_u64().unwrap(),
+    };
+
+    let encoded = account_state.encode();
+    assert_eq!(
+        hex::encode(&encoded),
+        vector["encoded"].as_str().unwrap().trim_start_matches("0x")
+    );
+    assert_eq!(AccountState::decode(&encoded).unwrap(), account_state);
+
+    //Statement kind is the third last word, unknown kinds are rejected.
+    let mut unknown_kind = encoded.clone();
+    let kind_index = unknown_kind.len() - 65;
+    unknown_kind[kind_index] = 6;
+    assert!(AccountState::decode(&unknown_kind).is_err());
+
+    //Account key is derived by the contract from the app account id.
+    let account_key =
+        AppAccountId::from(H256::from(bytes32(&vector["app_account_id"]))).account_key();
+    assert_eq!(account_key, H256::from(bytes32(&vector["account_key"])));
+
+    //Root of a tree with only this account, as computed by the contract.
+    let (_, state) = setup_components(db_path);
+    let mut state_lock = state.lock().await;
+    let (_, state_update) = state_lock
+        .update_set(HashMap::from([(account_key, Some(account_state))]), 1)
+        .expect("State update failed");
+
+    assert_eq!(
+        state_update.post_state_root,
+        H256::from(bytes32(&vector["state_root"]))
+    );
+}
+
+#[tokio::test]
+async fn test_mempool_nonces() {
+    use nexus_core::mempool::{Mempool, MAX_NONCE_GAP};
+    use nexus_core::types::{CloseAccount, RotateStatement};
+
+    let db_path = "./tests/db/test_mempool_nonces";
+    if let Err(e) = tokio::fs::remove_dir_all(db_path).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let (node_db, state) = setup_components(db_path);
+    {
+        let mut state_lock = state.lock().await;
+        let mut account = AccountState::zero();
+        account.statement = TEST_STATEMENT;
+        account.owner = TEST_SIGNING_KEY.public_key();
+        account.nonce = 5;
+        let (tree_update, _) = state_lock
+            .update_set(
+                HashMap::from([(test_app_account_id().account_key(), Some(account))]),
+                1,
+            )
+            .unwrap();
+        state_lock.commit(&tree_update.node_batch).unwrap();
+        state_lock.update_version(1).unwrap();
+    }
+    let mempool = Mempool::new(node_db, state);
+    let close_tx = |nonce: u64, key: &TxSigningKey| {
+        Transaction::new_signed(
+            TxParams::CloseAccount(CloseAccount {
+                app_id: test_app_account_id(),
+            }),
+            nonce,
+            key,
+        )
+    };
+
+    //Stale nonce.
+    assert!(mempool
+        .add_tx(close_tx(4, &TEST_SIGNING_KEY))
+        .await
+        .is_err());
+    //Initiated accounts can not be registered again.
+    assert!(mempool
+        .add_tx(init_account_tx(H256::zero(), 5))
+        .await
+        .is_err());
+    //Only the owner can take a nonce, and a copy with an invalid signature
+    //does not take the hash of the owner's transaction.
+    assert!(mempool
+        .add_tx(close_tx(5, &TxSigningKey([2u8; 32])))
+        .await
+        .is_err());
+    let mut forged_tx = close_tx(5, &TEST_SIGNING_KEY);
+    forged_tx.signature = close_tx(6, &TEST_SIGNING_KEY).signature;
+    assert!(mempool.add_tx(forged_tx).await.is_err());
+
+    let tx = close_tx(5, &TEST_SIGNING_KEY);
+    mempool.add_tx(tx.clone()).await.unwrap();
+    //Duplicate transaction, and a different transaction with the same nonce.
+    assert!(mempool.add_tx(tx.clone()).await.is_err());
+    let rotate_tx = Transaction::new_signed(
+        TxParams::RotateStatement(RotateStatement {
+            app_id: test_app_account_id(),
+            statement: TEST_STATEMENT,
+            statement_kind: ZKVM::proof_kind(),
+            effective_height: None,
+        }),
+        5,
+        &TEST_SIGNING_KEY,
+    );
+    assert!(mempool.add_tx(rotate_tx).await.is_err());
+
+    //Future nonces are only accepted up to the maximum gap.
+    let future_tx = close_tx(5 + MAX_NONCE_GAP, &TEST_SIGNING_KEY);
+    mempool.add_tx(future_tx.clone()).await.unwrap();
+    assert!(mempool
+        .add_tx(close_tx(6 + MAX_NONCE_GAP, &TEST_SIGNING_KEY))
+        .await
+        .is_err());
+
+    //Transactions after a nonce gap stay in pool until it is filled.
+    let txs = mempool.get_current_txs().await;
+    assert_eq!(txs, vec![tx.clone()]);
+    mempool.clear_txs(&txs).await;
+    assert!(mempool.get_current_txs().await.is_empty());
+
+    for nonce in 6..5 + MAX_NONCE_GAP {
+        mempool
+            .add_tx(close_tx(nonce, &TEST_SIGNING_KEY))
+            .await
+            .unwrap();
+    }
+    let txs = mempool.get_current_txs().await;
+    assert_eq!(
+        txs.iter().map(|tx| tx.nonce).collect::<Vec<u64>>(),
+        (6..=5 + MAX_NONCE_GAP).collect::<Vec<u64>>()
+    );
+    assert_eq!(txs.last(), Some(&future_tx));
+}
+
+#[tokio::test]
+async fn test_storage_slots() {
+    use jmt::{KeyHash, RootHash};
+    use tokio::fs;
+    let db_path = "./tests/db/test_storage_slots";
+    let app_account_id = AppAccountId::from(AppId(100));
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    let mut account = AccountState::zero();
+    account.owner = TEST_SIGNING_KEY.public_key();
+
+    let slot = H256::from([1u8; 32]);
+    let value = H256::from([2u8; 32]);
+    let unset_slot = H256::from([3u8; 32]);
+    let key = app_account_id.storage_key(0, &slot);
+
+    let (_, state) = setup_components(db_path);
+    let mut state_lock = state.lock().await;
+    let (tree_update, state_update) = state_lock
+        .update_set_with_storage(
+            HashMap::from([(app_account_id.account_key(), Some(account.clone()))]),
+            HashMap::from([(key.clone(), Some(value.clone()))]),
+            1,
+        )
+        .expect("State update failed");
+    state_lock
+        .commit(&tree_update.node_batch)
+        .expect("State commit failed");
+
+    //Storage slots are not part of the pre state, as writes are blind.
+    assert_eq!(state_update.pre_state.len(), 1);
+    assert_eq!(
+        state_lock.get(&app_account_id.account_key(), 1).unwrap(),
+        Some(account.clone())
+    );
+
+    let (stored, proof) = state_lock.get_storage_with_proof(&key, 1).unwrap();
+    assert_eq!(stored, Some(value.clone()));
+    proof
+        .verify(
+            RootHash(state_update.post_state_root.as_fixed_slice().clone()),
+            KeyHash(key.as_fixed_slice().clone()),
+            Some(value.as_slice().to_vec()),
+        )
+        .expect("Storage slot proof should verify");
+
+    let unset_key = app_account_id.storage_key(0, &unset_slot);
+    let (stored, proof) = state_lock.get_storage_with_proof(&unset_key, 1).unwrap();
+    assert_eq!(stored, None);
+    proof
+        .verify(
+            RootHash(state_update.post_state_root.as_fixed_slice().clone()),
+            KeyHash(unset_key.as_fixed_slice().clone()),
+            None::<Vec<u8>>,
+        )
+        .expect("Non inclusion proof should verify");
+
+    //Slots of other accounts, or of the account before it was closed, do not share keys.
+    assert_ne!(AppAccountId::from(AppId(101)).storage_key(0, &slot), key);
+    assert_ne!(app_account_id.storage_key(1, &slot), key);
+
+    //Account ids can be any value, but an id equal to a storage key does not point at the slot.
+    assert!(state_lock.get(&key, 1).is_err());
+    assert_eq!(
+        state_lock
+            .get(&AppAccountId::from(key.clone()).account_key(), 1)
+            .unwrap(),
+        None
+    );
+
+    //Update storage transactions always change the account, with the nonce.
+    account.nonce = 1;
+    let (tree_update, _) = state_lock
+        .update_set_with_storage(
+            HashMap::from([(app_account_id.account_key(), Some(account.clone()))]),
+            HashMap::from([(key.clone(), None)]),
+            2,
+        )
+        .expect("State update failed");
+    state_lock
+        .commit(&tree_update.node_batch)
+        .expect("State commit failed");
+
+    assert_eq!(state_lock.get_storage_with_proof(&key, 2).unwrap().0, None);
+    assert_eq!(
+        state_lock.get(&app_account_id.account_key(), 2).unwrap(),
+        Some(account)
+    );
+}
+
+#[test]
+fn test_account_generation() {
+    use nexus_core::stf::StateTransitionFunction;
+    use nexus_core::types::{AvailHeader, CloseAccount, TransactionZKVM};
+
+    let file_content = std::fs::read_to_string("tests/data/avail_headers.json")
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+    let app_account_id = test_app_account_id();
+
+    let execute = |txs: &[Transaction], account: AccountState| {
+        let zkvm_txs: Vec<TransactionZKVM> = txs
+            .iter()
+            .map(|tx| TransactionZKVM {
+                signature: tx.signature.clone(),
+                nonce: tx.nonce,
+                params: tx.params.clone(),
+            })
+            .collect();
+
+        let (post_state, _, tx_results) = StateTransitionFunction::<ZKVM>::new()
+            .execute_batch_with_results(
+                &AvailHeader::from(&headers[0]),
+                &HeaderStore::new(32),
+                &zkvm_txs,
+                &HashMap::from([(app_account_id.0.clone(), account)]),
+                |_: &NexusProof, _: &NexusRollupPI| -> Result<(), Error> {
+                    Err(Error::msg("No proofs are submitted in this batch."))
+                },
+            )
+            .expect("Batch execution failed");
+
+        (
+            post_state.get(&app_account_id.0).unwrap().clone(),
+            tx_results,
+        )
+    };
+
+    let init_tx = init_account_tx(H256::zero(), 0);
+    let close_tx = Transaction::new_signed(
+        TxParams::CloseAccount(CloseAccount {
+            app_id: app_account_id.clone(),
+        }),
+        1,
+        &TEST_SIGNING_KEY,
+    );
+
+    let (closed, tx_results) = execute(&[init_tx.clone(), close_tx.clone()], AccountState::zero());
+    assert!(tx_results.values().all(|result| result.is_ok()));
+
+    //Closed leaf keeps the nonce, and is not an initiated account.
+    assert!(!closed.is_initiated());
+    assert_eq!(closed.generation, 1);
+    assert_eq!(closed.nonce, 2);
+    assert_eq!(closed.owner, [0u8; 32]);
+    assert_ne!(closed, AccountState::zero());
+
+    //Txs signed before the close cannot be replayed.
+    let (account, tx_results) = execute(&[init_tx.clone()], closed.clone());
+    assert_eq!(account, closed);
+    assert_eq!(
+        tx_results.get(&init_tx.hash()),
+        Some(&Err(TxError::InvalidNonce {
+            expected: 2,
+            got: 0
+        }))
+    );
+
+    //Account initiated again is in the next generation, so it starts with empty storage.
+    let (account, tx_results) = execute(&[init_account_tx(H256::zero(), 2)], closed.clone());
+    assert!(tx_results.values().all(|result| result.is_ok()));
+    assert!(account.is_initiated());
+    assert_eq!(account.generation, 1);
+    assert_eq!(account.nonce, 3);
+    assert_eq!(account.owner, TEST_SIGNING_KEY.public_key());
+
+    let (account, tx_results) = execute(&[close_tx.clone()], account);
+    assert_eq!(
+        tx_results.get(&close_tx.hash()),
+        Some(&Err(TxError::InvalidNonce {
+            expected: 3,
+            got: 1
+        }))
+    );
+    assert!(account.is_initiated());
+}
+
+#[test]
+fn test_unsupported_statement_kind() {
+    use nexus_core::stf::StateTransitionFunction;
+    use nexus_core::types::{AvailHeader, TransactionZKVM};
+
+    let file_content = std::fs::read_to_string("tests/data/avail_headers.json")
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+    let app_account_id = test_app_account_id();
+
+    //Proofs of another zkvm are not verified by recursion.
+    let unsupported_kind = if ZKVM::proof_kind() == ProofKind::Risc0Receipt {
+        ProofKind::Sp1Compressed
+    } else {
+        ProofKind::Risc0Receipt
+    };
+    let tx = Transaction::new_signed(
+        TxParams::InitAccount(InitAccount {
+            app_id: app_account_id.clone(),
+            statement: TEST_STATEMENT,
+            statement_kind: unsupported_kind.clone(),
+            start_nexus_hash: H256::zero(),
+            owner: TEST_SIGNING_KEY.public_key(),
+        }),
+        0,
+        &TEST_SIGNING_KEY,
+    );
+    let txs = vec![TransactionZKVM {
+        signature: tx.signature.clone(),
+        nonce: tx.nonce,
+        params: tx.params.clone(),
+    }];
+
+    let (post_state, _, tx_results) = StateTransitionFunction::<ZKVM>::new()
+        .execute_batch_with_results(
+            &AvailHeader::from(&headers[0]),
+            &HeaderStore::new(32),
+            &txs,
+            &HashMap::from([(app_account_id.0.clone(), AccountState::zero())]),
+            |_: &NexusProof, _: &NexusRollupPI| -> Result<(), Error> {
+                Err(Error::msg("No proofs are submitted in this batch."))
+            },
+        )
+        .expect("Batch execution failed");
+
+    assert_eq!(
+        tx_results.get(&tx.hash()),
+        Some(&Err(TxError::UnsupportedProofKind {
+            kind: unsupported_kind
+        }))
+    );
+    assert_eq!(
+        post_state.get(&app_account_id.0),
+        Some(&AccountState::zero())
+    );
+}
+
+#[test]
+fn test_pre_state_root_linked_to_previous_header() {
+    use jmt::proof::UpdateMerkleProof;
+    use nexus_core::state::MultiProof;
+    use nexus_core::types::{AvailHeader, Sha256, StateUpdate};
+    use nexus_core::zkvm_state_machine::ZKVMStateMachine;
+
+    let file_content = std::fs::read_to_string("tests/data/avail_headers.json")
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+    let state_update = |root: H256| StateUpdate {
+        pre_state_root: root.clone(),
+        post_state_root: root,
+        pre_state: HashMap::new(),
+        pre_state_proof: MultiProof::default(),
+        update_proof: UpdateMerkleProof::<Sha256>::new(vec![]),
+    };
+    let state_machine = ZKVMStateMachine::<ZKVM>::new();
+
+    //Genesis case, the previous state is the empty tree.
+    let genesis_header = state_machine
+        .execute_batch(
+            &AvailHeader::from(&headers[0]),
+            &HeaderStore::new(32),
+            &vec![],
+            state_update(H256::zero()),
+        )
+        .expect("Genesis batch should start from the empty tree");
+    assert!(state_machine
+        .execute_batch(
+            &AvailHeader::from(&headers[0]),
+            &HeaderStore::new(32),
+            &vec![],
+            state_update(H256::from([1u8; 32])),
+        )
+        .is_err());
+
+    let mut previous_header = genesis_header;
+    previous_header.state_root = H256::from([1u8; 32]);
+    let mut old_headers = HeaderStore::new(32);
+    old_headers.push_front(&previous_header);
+
+    let header = state_machine
+        .execute_batch(
+            &AvailHeader::from(&headers[1]),
+            &old_headers,
+            &vec![],
+            state_update(H256::from([1u8; 32])),
+        )
+        .expect("Batch should start from the state root of the previous header");
+    assert_eq!(header.prev_state_root, previous_header.state_root);
+
+    //Host cannot pick another pre state root than the one in the previous header.
+    assert!(state_machine
+        .execute_batch(
+            &AvailHeader::from(&headers[1]),
+            &old_headers,
+            &vec![],
+            state_update(H256::from([2u8; 32])),
+        )
+        .is_err());
+}
+
+#[test]
+fn test_zkvm_backend_selection() {
+    use host::zkvm::{BackendProof, ZKVMBackend};
+    use std::str::FromStr;
+
+    #[cfg(any(feature = "risc0"))]
+    {
+        assert_eq!(ZKVMBackend::from_str("risc0").unwrap(), ZKVMBackend::Risc0);
+        assert_eq!(ZKVMBackend::from_str("RISC0").unwrap(), ZKVMBackend::Risc0);
+        assert_eq!(
+            <nexus_core::zkvm::risczero::RiscZeroProof as BackendProof>::BACKEND,
+            ZKVMBackend::Risc0
+        );
+    }
+    #[cfg(any(feature = "sp1"))]
+    {
+        assert_eq!(ZKVMBackend::from_str("sp1").unwrap(), ZKVMBackend::Sp1);
+        assert_eq!(
+            <nexus_core::zkvm::sp1::Sp1Proof as BackendProof>::BACKEND,
+            ZKVMBackend::Sp1
+        );
+    }
+    #[cfg(any(feature = "mock"))]
+    {
+        assert_eq!(ZKVMBackend::from_str("mock").unwrap(), ZKVMBackend::Mock);
+        assert_eq!(
+            <nexus_core::zkvm::mock::MockProof as BackendProof>::BACKEND,
+            ZKVMBackend::Mock
+        );
+    }
+
+    assert!(ZKVMBackend::from_str("jolt").is_err());
+}
+
+#[cfg(any(feature = "mock", feature = "risc0"))]
+#[tokio::test]
+async fn test_run_nexus_with_backend() {
+    use host::run_nexus_with_backend;
+    use host::zkvm::BackendProof;
+    use tokio::fs;
+    let db_path = "./tests/db/test_run_nexus_with_backend";
+
+    if let Err(e) = fs::remove_dir_all(db_path).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let mut mock_relayer = MockRelayer::new();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    let file_content = fs::read_to_string("tests/data/avail_headers.json")
+        .await
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers.clone();
+        let sender_in_box = sender.clone();
+        let node_db_in_box = node_db_clone.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let start_nexus_hash = latest_nexus_header(&node_db_in_box).await.hash();
+
+            //Only accepted by the sp1 backend, which is not the one selected.
+            let foreign_init_tx = Transaction::new_signed(
+                TxParams::InitAccount(InitAccount {
+                    app_id: test_app_account_id(),
+                    statement: TEST_STATEMENT,
+                    statement_kind: ProofKind::Sp1Compressed,
+                    start_nexus_hash: start_nexus_hash.clone(),
+                    owner: TEST_SIGNING_KEY.public_key(),
+                }),
+                0,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7014, &foreign_init_tx).await;
+            sender_in_box
+                .send(headers_in_box[1].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let init_tx = init_account_tx(start_nexus_hash.clone(), 0);
+            send_tx(7014, &init_tx).await;
+            sender_in_box
+                .send(headers_in_box[2].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            //Proofs of the selected backend are verified by the node.
+            let anchor = latest_nexus_header(&node_db_in_box).await.hash();
+            let proof_tx = Transaction::new_signed(
+                TxParams::SubmitProof(submit_proof_params(start_nexus_hash, anchor, 1)),
+                1,
+                &TEST_SIGNING_KEY,
+            );
+            send_tx(7014, &proof_tx).await;
+            sender_in_box
+                .send(headers_in_box[3].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            let mut statuses = vec![];
+            for tx in [&foreign_init_tx, &init_tx, &proof_tx] {
+                let response = Client::new()
+                    .get(format!(
+                        "http://127.0.0.1:7014/tx_status?tx_hash={}",
+                        hex::encode(tx.hash().as_slice())
+                    ))
+                    .send()
+                    .await
+                    .unwrap();
+                let tx_status: TransactionWithStatus = response.json().await.unwrap();
+                statuses.push((tx_status.status, tx_status.error));
+            }
+
+            assert_eq!(
+                statuses,
+                vec![
+                    (
+                        TransactionStatus::Failed,
+                        Some(TxError::UnsupportedProofKind {
+                            kind: ProofKind::Sp1Compressed
+                        })
+                    ),
+                    (TransactionStatus::Successful, None),
+                    (TransactionStatus::Successful, None),
+                ]
+            );
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    //Dispatches to the backend the test proofs are built for.
+    match run_nexus_with_backend(
+        <Proof as BackendProof>::BACKEND,
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db,
+        (ProverMode::MockProof, 7014),
+        state,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[cfg(any(feature = "mock"))]
+#[test]
+fn test_mock_prover() {
+    use nexus_core::types::Proof as NexusProof;
+    use nexus_core::zkvm::mock::{MockProof, MockProver, MockZKVM};
+    use nexus_core::zkvm::traits::{ZKVMEnv, ZKVMProof, ZKVMProver};
+
+    fn guest() {
+        let input: u64 = MockZKVM::read_input().unwrap();
+        MockZKVM::commit(&(input + 1));
+    }
+
+    fn verifying_guest() {
+        let img_id: [u32; 8] = MockZKVM::read_input().unwrap();
+        MockZKVM::verify(img_id, &42u64).unwrap();
+        MockZKVM::commit(&true);
+    }
+
+    let elf = b"test_guest".to_vec();
+    let mut prover = MockProver::new(elf.clone(), ProverMode::MockProof).with_guest(guest);
+    prover.add_input(&41u64).unwrap();
+    let mut proof = prover.prove().unwrap();
+
+    assert_eq!(proof.public_inputs::<u64>().unwrap(), 42);
+    assert!(proof.verify(None, Some(elf), ProverMode::MockProof).is_ok());
+    assert!(proof
+        .verify(None, Some(b"other_guest".to_vec()), ProverMode::MockProof)
+        .is_err());
+
+    let nexus_proof: NexusProof = proof.clone().try_into().unwrap();
+    assert_eq!(nexus_proof.kind, ProofKind::Mock);
+    assert_eq!(
+        MockProof::try_from(nexus_proof).unwrap().public_values,
+        proof.public_values
+    );
+
+    //Verification in the guest passes only when the proof is added for recursion.
+    let mut prover =
+        MockProver::new(b"verifier".to_vec(), ProverMode::MockProof).with_guest(verifying_guest);
+    prover.add_input(&proof.image_id).unwrap();
+    assert!(prover.prove().is_err());
+
+    let mut prover =
+        MockProver::new(b"verifier".to_vec(), ProverMode::MockProof).with_guest(verifying_guest);
+    prover.add_input(&proof.image_id).unwrap();
+    prover.add_proof_for_recursion(proof).unwrap();
+    assert!(prover.prove().unwrap().public_inputs::<bool>().unwrap());
+}
+
+#[cfg(any(feature = "mock"))]
+#[tokio::test]
+async fn test_execute_only_stats() {
+    use nexus_core::zkvm::ExecutionStats;
+    use serde_json;
+    use tokio::fs;
+    let db_path = "./tests/db/test_execute_only_stats";
+
+    if let Err(e) = fs::remove_dir_all(db_path.clone()).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    } else {
+        println!("Database folder cleaned up successfully.");
+    }
+
+    let mut mock_relayer = MockRelayer::new();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<Header>();
+    let receiver_arc: Arc<Mutex<UnboundedReceiver<Header>>> = Arc::new(Mutex::new(receiver));
+    let receiver_arc_clone = receiver_arc.clone();
+
+    mock_relayer
+        .expect_receiver()
+        .returning(move || receiver_arc_clone.clone());
+    mock_relayer.expect_stop().returning(move || ());
+
+    let file_content = fs::read_to_string("tests/data/avail_headers.json")
+        .await
+        .expect("Failed to read headers JSON file");
+    let headers: Vec<Header> =
+        serde_json::from_str(&file_content).expect("Failed to parse headers JSON file");
+
+    let headers_clone = headers.clone();
+    let sender_clone = sender.clone();
+
+    let prover_mode = ProverMode::ExecuteOnly;
+    let (node_db, state) = setup_components(db_path);
+    let node_db_clone = node_db.clone();
+    let node_db_clone_2 = node_db.clone();
+    let state_clone = state.clone();
+    let state_machine = StateMachine::<ZKVM, Proof>::new(state_clone.clone());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    mock_relayer.expect_start().returning(move |_| {
+        let headers_in_box = headers_clone.clone();
+        let sender_in_box = sender_clone.clone();
+        let node_db_in_box = node_db_clone_2.clone();
+        let shutdown_tx_clone = shutdown_tx.clone();
+
+        Box::pin(async move {
+            let block_stats = |block_hash: H256| {
+                let node_db = node_db_in_box.clone();
+                async move {
+                    node_db
+                        .lock()
+                        .await
+                        .get::<ExecutionStats>(&[block_hash.as_slice(), b"-stats"].concat())
+                        .expect("Unexpected internal db error")
+                }
+            };
+
+            sender_in_box
+                .send(headers_in_box[0].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            //Batches without transactions report no transaction cycles.
+            let empty_header = latest_nexus_header(&node_db_in_box).await;
+            let stats = block_stats(empty_header.hash())
+                .await
+                .expect("Stats not recorded in execute only mode");
+            assert!(stats.tx_cycles.is_empty());
+
+            let start_nexus_hash = empty_header.hash();
+            send_tx(7013, &init_account_tx(start_nexus_hash.clone(), 0)).await;
+            //Fails on the proof, but is still marked as a transaction of the batch.
+            let mut mismatched_params =
+                submit_proof_params(start_nexus_hash.clone(), start_nexus_hash.clone(), 1);
+            mismatched_params.state_root = H256::from([42u8; 32]);
+            send_tx(
+                7013,
+                &Transaction::new_signed(
+                    TxParams::SubmitProof(mismatched_params),
+                    1,
+                    &TEST_SIGNING_KEY,
+                ),
+            )
+            .await;
+            sender_in_box
+                .send(headers_in_box[1].clone())
+                .expect("Failed to send header in mock");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            //Guest is told to mark each transaction, which the mock reports with no cycles.
+            let header = latest_nexus_header(&node_db_in_box).await;
+            let stats = block_stats(header.hash())
+                .await
+                .expect("Stats not recorded in execute only mode");
+            assert_eq!(stats.tx_cycles, vec![0, 0]);
+            assert_eq!(stats.total_cycles, 0);
+
+            shutdown_tx_clone.send(true).unwrap();
+        })
+    });
+
+    match run_nexus(
+        Arc::new(Mutex::new(mock_relayer)),
+        node_db_clone.clone(),
+        state_machine,
+        (prover_mode, 7013),
+        state_clone,
+        shutdown_rx,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => {
+            panic!("Nexus exited with unexpected error: {:?}", e);
+        }
+    };
+}
+
+#[test]
+fn test_execution_stats_tx_markers() {
+    use nexus_core::zkvm::{tx_cycle_marker, ExecutionStats};
+
+    let stats = ExecutionStats {
+        total_cycles: 1 << 20,
+        user_cycles: 900_000,
+        segments: 1,
+        tx_cycles: vec![],
+    }
+    .with_tx_markers(vec![
+        (tx_cycle_marker(2), 300),
+        (String::from("batch"), 1_000),
+        (tx_cycle_marker(0), 100),
+        (tx_cycle_marker(10), 50),
+    ]);
+
+    //Transactions without markers are reported with zero cycles, other labels are ignored.
+    let mut expected = vec![0u64; 11];
+    expected[0] = 100;
+    expected[2] = 300;
+    expected[10] = 50;
+    assert_eq!(stats.tx_cycles, expected);
+    assert_eq!(stats.total_cycles, 1 << 20);
+}
+
+#[test]
+fn test_state_restore() {
+    use nexus_core::state::VmState;
+
+    let source_path = "./tests/db/test_state_restore_source";
+    let invalid_path = "./tests/db/test_state_restore_invalid";
+    let interrupted_path = "./tests/db/test_state_restore_interrupted";
+    let target_path = "./tests/db/test_state_restore_target";
+    for path in [source_path, invalid_path, interrupted_path, target_path] {
+        if let Err(e) = std::fs::remove_dir_all(path) {
+            eprintln!("Failed to clean up database folder: {:?}", e);
+        }
+    }
+
+    let mut source = VmState::new(source_path);
+    let accounts: HashMap<H256, Option<AccountState>> = (0u8..5)
+        .map(|i| {
+            let mut account = AccountState::zero();
+            account.nonce = i as u64;
+            (H256::from([i; 32]), Some(account))
+        })
+        .collect();
+    let (tree_update, state_update) = source.update_set(accounts.clone(), 1).unwrap();
+    source.update_version(1).unwrap();
+    source.commit(&tree_update.node_batch).unwrap();
+
+    //Leaves are streamed in chunks, each with a proof against the root.
+    let mut chunks = vec![];
+    let mut start_after = None;
+    while let Some((leaves, proof)) = source.get_leaves_chunk(1, start_after, 2).unwrap() {
+        start_after = leaves.last().map(|(key_hash, _)| *key_hash);
+        chunks.push((leaves, proof));
+    }
+    assert_eq!(chunks.len(), 3);
+
+    //Chunks are not accepted against a root they were not proven for.
+    let mut invalid = VmState::new(invalid_path);
+    assert!(invalid
+        .restore(1, H256::from([9u8; 32]), chunks.clone())
+        .is_err());
+    //Values of a chunk are only written once it is verified.
+    assert_eq!(invalid.get_version(true).unwrap(), None);
+    for (key, _) in accounts.iter() {
+        assert_eq!(invalid.get(key, 1).unwrap(), None);
+    }
+
+    //Nodes written without a version, as left by an interrupted restore, are
+    //cleared when the restore is started again.
+    let mut interrupted = VmState::new(interrupted_path);
+    let stale_accounts: HashMap<H256, Option<AccountState>> = (10u8..13)
+        .map(|i| (H256::from([i; 32]), Some(AccountState::zero().closed())))
+        .collect();
+    let (tree_update, _) = interrupted.update_set(stale_accounts.clone(), 1).unwrap();
+    interrupted.commit(&tree_update.node_batch).unwrap();
+    assert_eq!(interrupted.get_version(true).unwrap(), None);
+
+    interrupted
+        .restore(1, state_update.post_state_root.clone(), chunks.clone())
+        .unwrap();
+    assert_eq!(
+        interrupted.get_root(1).unwrap(),
+        state_update.post_state_root
+    );
+    for (key, _) in stale_accounts.iter() {
+        assert_eq!(interrupted.get(key, 1).unwrap(), None);
+    }
+    for (key, account) in accounts.iter() {
+        assert_eq!(interrupted.get(key, 1).unwrap(), account.clone());
+    }
+
+    let mut target = VmState::new(target_path);
+    target
+        .restore(1, state_update.post_state_root.clone(), chunks)
+        .unwrap();
+
+    assert_eq!(target.get_root(1).unwrap(), state_update.post_state_root);
+    assert_eq!(target.get_version(true).unwrap(), Some(1));
+    for (key, account) in accounts {
+        assert_eq!(target.get(&key, 1).unwrap(), account);
+    }
+
+    //Restores are only applied to empty stores.
+    assert!(target
+        .restore(1, state_update.post_state_root, vec![])
+        .is_err());
+}
+
+#[test]
+fn test_min_retained_version() {
+    use host::pruning::min_retained_version;
+
+    //Versions of blocks inside the header window are kept beyond the retention.
+    assert_eq!(min_retained_version(100, 10, vec![]), 91);
+    assert_eq!(min_retained_version(100, 10, vec![95, 80]), 80);
+    assert_eq!(min_retained_version(5, 10, vec![]), 0);
+    assert_eq!(min_retained_version(100, 0, vec![]), 100);
+}
+
+#[tokio::test]
+async fn test_genesis() {
+    use host::genesis::{adopt_legacy_db, init_genesis};
+    use host::AvailToNexusPointer;
+    use nexus_core::types::{GenesisAccount, GenesisConfig, NexusBlockWithPointers};
+
+    let paths = [
+        "./tests/db/test_genesis",
+        "./tests/db/test_genesis_replica",
+        "./tests/db/test_genesis_legacy",
+    ];
+    for path in paths {
+        if let Err(e) = tokio::fs::remove_dir_all(path).await {
+            eprintln!("Failed to clean up database folder: {:?}", e);
+        }
+    }
+
+    let config = GenesisConfig {
+        avail_start_height: 100,
+        avail_start_hash: Some(H256::from([5u8; 32])),
+        header_window: 10,
+        accounts: (1u32..=2)
+            .map(|i| GenesisAccount {
+                app_id: AppAccountId::from(AppId(i)),
+                statement: TEST_STATEMENT,
+                statement_kind: ProofKind::Mock,
+                start_nexus_hash: H256::from([i as u8; 32]),
+                owner: TEST_SIGNING_KEY.public_key(),
+            })
+            .collect(),
+    };
+    let relayer_mutex = Arc::new(Mutex::new(MockRelayer::new()));
+
+    let (node_db, state) = setup_components(paths[0]);
+    let genesis_header = init_genesis(&relayer_mutex, &node_db, &state, config.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(genesis_header.number, 0);
+    assert_eq!(genesis_header.parent_hash, H256::zero());
+    assert_eq!(genesis_header.avail_header_hash, H256::from([5u8; 32]));
+    assert_eq!(genesis_header.header_window, 10);
+    assert_eq!(latest_nexus_header(&node_db).await, genesis_header);
+
+    //Accounts are pre-seeded in the genesis state root.
+    {
+        let db_lock = node_db.lock().await;
+        let block = db_lock
+            .get::<NexusBlockWithPointers>(&[genesis_header.hash().as_slice(), b"-block"].concat())
+            .unwrap()
+            .unwrap();
+        let state_lock = state.lock().await;
+        assert_eq!(
+            state_lock.get_root(block.jmt_version).unwrap(),
+            genesis_header.state_root
+        );
+
+        for account in config.accounts.iter() {
+            let account_state = state_lock
+                .get(&account.app_id.account_key(), block.jmt_version)
+                .unwrap()
+                .expect("Genesis account not in state");
+            assert!(account_state.is_initiated());
+            assert_eq!(account_state.statement, account.statement);
+            assert_eq!(account_state.statement_kind, account.statement_kind);
+            assert_eq!(
+                account_state.start_nexus_hash,
+                account.start_nexus_hash.as_fixed_slice().clone()
+            );
+            assert_eq!(account_state.owner, account.owner);
+            assert_eq!(account_state.nonce, 0);
+        }
+    }
+
+    //Independent nodes build the same genesis.
+    let (replica_db, replica_state) = setup_components(paths[1]);
+    let replica_header = init_genesis(&relayer_mutex, &replica_db, &replica_state, config.clone())
+        .await
+        .unwrap();
+    assert_eq!(replica_header.hash(), genesis_header.hash());
+
+    //Restarting with the same genesis returns the stored header, any other genesis is rejected.
+    assert_eq!(
+        init_genesis(&relayer_mutex, &node_db, &state, config.clone())
+            .await
+            .unwrap(),
+        genesis_header
+    );
+    let mut other_config = config.clone();
+    other_config.accounts.pop();
+    let error = init_genesis(&relayer_mutex, &node_db, &state, other_config)
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Genesis config does not match the genesis the node was started with."));
+
+    //Node db with blocks built before genesis configs existed.
+    let (legacy_db, legacy_state) = setup_components(paths[2]);
+    let legacy_header = NexusHeader {
+        parent_hash: H256::zero(),
+        prev_state_root: H256::zero(),
+        state_root: H256::zero(),
+        tx_root: H256::zero(),
+        receipts_root: H256::zero(),
+        message_root: H256::zero(),
+        avail_header_hash: H256::from([6u8; 32]),
+        number: 0,
+        header_window: 32,
+    };
+    {
+        let mut header_store = HeaderStore::new(32);
+        header_store.push_front(&legacy_header);
+        let db_lock = legacy_db.lock().await;
+        db_lock.put(b"previous_headers", &header_store).unwrap();
+        db_lock
+            .put(legacy_header.hash().as_slice(), &legacy_header)
+            .unwrap();
+        db_lock
+            .put(
+                &[0u32.to_be_bytes().as_slice(), b"-block"].concat(),
+                &legacy_header.hash(),
+            )
+            .unwrap();
+        db_lock
+            .put(
+                legacy_header.avail_header_hash.as_slice(),
+                &AvailToNexusPointer {
+                    number: 10000,
+                    nexus_hash: legacy_header.hash(),
+                },
+            )
+            .unwrap();
+    }
+
+    let legacy_config = GenesisConfig {
+        avail_start_height: 10000,
+        avail_start_hash: Some(legacy_header.avail_header_hash.clone()),
+        header_window: 32,
+        accounts: vec![],
+    };
+    assert!(init_genesis(
+        &relayer_mutex,
+        &legacy_db,
+        &legacy_state,
+        legacy_config.clone()
+    )
+    .await
+    .is_err());
+
+    //Legacy chains can only be adopted with a genesis describing their first block.
+    let mut mismatched_config = legacy_config.clone();
+    mismatched_config.avail_start_height = 9999;
+    assert!(
+        adopt_legacy_db(&relayer_mutex, &legacy_db, mismatched_config)
+            .await
+            .is_err()
+    );
+    assert!(adopt_legacy_db(&relayer_mutex, &legacy_db, config.clone())
+        .await
+        .is_err());
+
+    adopt_legacy_db(&relayer_mutex, &legacy_db, legacy_config.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        init_genesis(&relayer_mutex, &legacy_db, &legacy_state, legacy_config)
+            .await
+            .unwrap(),
+        legacy_header
+    );
+}
+
+#[tokio::test]
+async fn test_genesis_config_validation() {
+    use host::genesis::{init_genesis, load_genesis_config, validate_genesis_config};
+    use host::zkvm::BackendProof;
+    use nexus_core::types::{GenesisAccount, GenesisConfig};
+
+    let path = "./tests/db/test_genesis_config_validation";
+    if let Err(e) = tokio::fs::remove_dir_all(path).await {
+        eprintln!("Failed to clean up database folder: {:?}", e);
+    }
+
+    let backend = <Proof as BackendProof>::BACKEND;
+    let config = GenesisConfig {
+        avail_start_height: 100,
+        avail_start_hash: Some(H256::from([5u8; 32])),
+        header_window: 10,
+        accounts: vec![GenesisAccount {
+            app_id: test_app_account_id(),
+            statement: TEST_STATEMENT,
+            statement_kind: ZKVM::proof_kind(),
+            start_nexus_hash: H256::zero(),
+            owner: TEST_SIGNING_KEY.public_key(),
+        }],
+    };
+    assert!(validate_genesis_config(&config, &backend).is_ok());
+
+    //Configs are validated when loaded from the genesis file.
+    tokio::fs::create_dir_all(path).await.unwrap();
+    let config_path = format!("{}/genesis.json", path);
+    let mut zero_window_config = config.clone();
+    zero_window_config.header_window = 0;
+    tokio::fs::write(
+        &config_path,
+        serde_json::to_string(&zero_window_config).unwrap(),
+    )
+    .await
+    .unwrap();
+    let error = load_genesis_config(&config_path, &backend)
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Header window in genesis config has to be greater than 0."));
+    tokio::fs::write(&config_path, serde_json::to_string(&config).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        load_genesis_config(&config_path, &backend).await.unwrap(),
+        config
+    );
+
+    let mut unsupported_kind_config = config.clone();
+    unsupported_kind_config.accounts[0].statement_kind =
+        if ZKVM::proof_kind() == ProofKind::Risc0Receipt {
+            ProofKind::Sp1Compressed
+        } else {
+            ProofKind::Risc0Receipt
+        };
+    assert!(validate_genesis_config(&unsupported_kind_config, &backend).is_err());
+
+    let mut zero_statement_config = config.clone();
+    zero_statement_config.accounts[0].statement = StatementDigest::zero();
+    assert!(validate_genesis_config(&zero_statement_config, &backend).is_err());
+
+    //Not a point on the curve.
+    let mut invalid_owner_config = config.clone();
+    let mut invalid_owner = [0u8; 32];
+    invalid_owner[0] = 2;
+    invalid_owner_config.accounts[0].owner = invalid_owner;
+    assert!(validate_genesis_config(&invalid_owner_config, &backend).is_err());
+
+    //Header store of the node db has to follow the header window of the genesis.
+    let relayer_mutex = Arc::new(Mutex::new(MockRelayer::new()));
+    let (node_db, state) = setup_components(&format!("{}/db", path));
+    init_genesis(&relayer_mutex, &node_db, &state, config.clone())
+        .await
+        .unwrap();
+    {
+        let db_lock = node_db.lock().await;
+        let mut header_store = HeaderStore::new(32);
+        header_store.push_front(
+            &db_lock
+                .get::<NexusHeader>(b"genesis_header")
+                .unwrap()
+                .unwrap(),
+        );
+        db_lock.put(b"previous_headers", &header_store).unwrap();
+    }
+    let error = init_genesis(&relayer_mutex, &node_db, &state, config)
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Header window of genesis config does not match the header store"));
+}
+
+#[tokio::test]
+async fn test_state_snapshot() {
+    use host::genesis::init_genesis;
+    use host::snapshot::{export_snapshot, import_snapshot, load_snapshot, save_snapshot};
+    use nexus_core::types::{GenesisAccount, GenesisConfig, HeaderStore, StatementDigest};
+
+    let source_path = "./tests/db/test_state_snapshot_source";
+    let target_path = "./tests/db/test_state_snapshot_target";
+    let invalid_path = "./tests/db/test_state_snapshot_invalid";
+    let empty_path = "./tests/db/test_state_snapshot_empty";
+    let snapshot_path = "./tests/db/test_state_snapshot.bin";
+    for path in [source_path, target_path, invalid_path, empty_path] {
+        if let Err(e) = tokio::fs::remove_dir_all(path).await {
+            eprintln!("Failed to clean up database folder: {:?}", e);
+        }
+    }
+
+    let config = GenesisConfig {
+        avail_start_height: 100,
+        avail_start_hash: Some(H256::from([5u8; 32])),
+        header_window: 10,
+        accounts: (1u32..=3)
+            .map(|i| GenesisAccount {
+                app_id: AppAccountId::from(AppId(i)),
+                statement: StatementDigest::zero(),
+                statement_kind: ProofKind::Mock,
+                start_nexus_hash: H256::zero(),
+                owner: TEST_SIGNING_KEY.public_key(),
+            })
+            .collect(),
+    };
+    let relayer_mutex = Arc::new(Mutex::new(MockRelayer::new()));
+
+    let (node_db, state) = setup_components(source_path);
+    let genesis_header = init_genesis(&relayer_mutex, &node_db, &state, config.clone())
+        .await
+        .unwrap();
+
+    let snapshot = export_snapshot(&node_db, &state).await.unwrap();
+    assert_eq!(snapshot.block.block.header, genesis_header);
+    save_snapshot(&snapshot, snapshot_path).await.unwrap();
+
+    let (target_db, target_state) = setup_components(target_path);
+
+    //Snapshots are only imported for the block the operator trusts.
+    assert!(import_snapshot(
+        &target_db,
+        &target_state,
+        load_snapshot(snapshot_path).await.unwrap(),
+        &H256::from([1u8; 32]),
+    )
+    .await
+    .is_err());
+
+    //Header window has to link back from the trusted block.
+    let mut unlinked_snapshot = load_snapshot(snapshot_path).await.unwrap();
+    let mut fake_parent = genesis_header.clone();
+    fake_parent.state_root = H256::from([1u8; 32]);
+    unlinked_snapshot.header_store = HeaderStore::new(10);
+    unlinked_snapshot.header_store.push_front(&fake_parent);
+    unlinked_snapshot.header_store.push_front(&genesis_header);
+    assert!(import_snapshot(
+        &target_db,
+        &target_state,
+        unlinked_snapshot,
+        &genesis_header.hash(),
+    )
+    .await
+    .is_err());
+
+    let header = import_snapshot(
+        &target_db,
+        &target_state,
+        load_snapshot(snapshot_path).await.unwrap(),
+        &genesis_header.hash(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(header, genesis_header);
+
+    {
+        let state_lock = target_state.lock().await;
+        assert_eq!(state_lock.get_root(1).unwrap(), genesis_header.state_root);
+        for account in config.accounts.iter() {
+            assert_eq!(
+                state_lock
+                    .get(&account.app_id.account_key(), 1)
+                    .unwrap()
+                    .unwrap()
+                    .owner,
+                account.owner
+            );
+        }
+    }
+
+    //Node started from the snapshot still checks its genesis, and can not be imported into again.
+    assert_eq!(
+        init_genesis(&relayer_mutex, &target_db, &target_state, config)
+            .await
+            .unwrap(),
+        genesis_header
+    );
+    assert!(import_snapshot(
+        &target_db,
+        &target_state,
+        load_snapshot(snapshot_path).await.unwrap(),
+        &genesis_header.hash(),
+    )
+    .await
+    .is_err());
+
+    //State not matching the state root of the header is rejected.
+    let mut invalid_snapshot = load_snapshot(snapshot_path).await.unwrap();
+    invalid_snapshot.block.block.header.state_root = H256::from([9u8; 32]);
+    invalid_snapshot.header_store = HeaderStore::new(10);
+    invalid_snapshot
+        .header_store
+        .push_front(&invalid_snapshot.block.block.header);
+
+    let invalid_hash = invalid_snapshot.block.block.header.hash();
+
+    let (invalid_db, invalid_state) = setup_components(invalid_path);
+    assert!(
+        import_snapshot(&invalid_db, &invalid_state, invalid_snapshot, &invalid_hash)
+            .await
+            .is_err()
+    );
+    assert!(invalid_db
+        .lock()
+        .await
+        .get::<HeaderStore>(b"previous_headers")
+        .unwrap()
+        .is_none());
+
+    //Leaves cannot be imported for a block with an empty state root.
+    let mut empty_snapshot = load_snapshot(snapshot_path).await.unwrap();
+    empty_snapshot.block.block.header.state_root = H256::zero();
+    empty_snapshot.header_store = HeaderStore::new(10);
+    empty_snapshot
+        .header_store
+        .push_front(&empty_snapshot.block.block.header);
+    let empty_hash = empty_snapshot.block.block.header.hash();
+    assert!(!empty_snapshot.chunks.is_empty());
+
+    let (empty_db, empty_state) = setup_components(empty_path);
+    assert!(
+        import_snapshot(&empty_db, &empty_state, empty_snapshot, &empty_hash)
+            .await
+            .is_err()
+    );
+    assert_eq!(empty_state.lock().await.get_version(true).unwrap(), None);
+}
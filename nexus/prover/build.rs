@@ -1,9 +1,20 @@
 #[cfg(any(feature = "sp1"))]
-use sp1_build::build_program;
+use sp1_build::{build_program_with_args, BuildArgs};
 
 fn main() {
     #[cfg(any(feature = "risc0"))]
     risc0_build::embed_methods();
     #[cfg(any(feature = "sp1"))]
-    build_program("./sp1-guest")
+    build_program_with_args(
+        "./sp1-guest",
+        BuildArgs {
+            //Guest has to accept the same proof kinds as the node.
+            features: if cfg!(feature = "dev") {
+                vec!["dev".to_string()]
+            } else {
+                vec![]
+            },
+            ..Default::default()
+        },
+    )
 }
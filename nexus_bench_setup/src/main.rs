@@ -7,8 +7,9 @@ use nexus_core::state_machine::StateMachine;
 use nexus_core::types::{
     AccountWithProof, AppAccountId, AppId, AvailHeader, HeaderStore, InitAccount,
     NexusHeader, NexusRollupPI, StatementDigest, SubmitProof, Transaction, TxParams,
-    TxSignature, H256,
+    TxSigningKey, H256,
 };
+use nexus_core::zkvm::traits::ZKVMEnv;
 use nexus_core::zkvm::ProverMode;
 use nexus_host::execute_batch;
 use risc0_zkvm::{default_prover, ExecutorEnv};
@@ -26,9 +27,9 @@ use tokio::sync::Mutex;
 use std::path::{Path, PathBuf};
 
 #[cfg(feature = "risc0")]
-use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, RiscZeroProver as Prover, ZKVM};
+use nexus_core::zkvm::risczero::{RiscZeroProof as Proof, ZKVM};
 #[cfg(feature = "sp1")]
-use nexus_core::zkvm::sp1::{Sp1Proof as Proof, Sp1Prover as Prover, SP1ZKVM as ZKVM};
+use nexus_core::zkvm::sp1::{Sp1Proof as Proof, SP1ZKVM as ZKVM};
 
 #[cfg(any(feature = "sp1"))]
 use env_logger;
@@ -36,6 +37,8 @@ use env_logger;
 #[cfg(any(feature = "sp1"))]
 use log;
 
+const BENCH_SIGNING_KEY: TxSigningKey = TxSigningKey([1u8; 32]);
+
 #[derive(Clone, Serialize, Deserialize)]
 struct AdapterStateData {
     last_height: u32,
@@ -50,7 +53,9 @@ fn create_mock_data(
         ProverMode::NoAggregation => "no_aggregation",
         ProverMode::Compressed => "compressed",
         ProverMode::Groth16 => "groth16",
+        ProverMode::Plonk => "plonk",
         ProverMode::MockProof => "mock_proof",
+        ProverMode::ExecuteOnly => "execute_only",
     };
 
     let node_db_path = format!("./db/node_db_{}", prover_mode_string);
@@ -86,21 +91,24 @@ async fn generate_init_account_transactions(
     let mut init_account_transactions: Vec<Transaction> = Vec::new();
 
     for txn_index in 0..100 {
-        let tx = Transaction {
-            signature: TxSignature([0u8; 64]),
-            params: TxParams::InitAccount(InitAccount {
+        let tx = Transaction::new_signed(
+            TxParams::InitAccount(InitAccount {
                 app_id: AppAccountId::from(AppId(txn_index as u32)),
                 statement: StatementDigest(ADAPTER_ID),
+                statement_kind: ZKVM::proof_kind(),
                 start_nexus_hash: header.hash(),
+                owner: BENCH_SIGNING_KEY.public_key(),
             }),
-        };
+            0,
+            &BENCH_SIGNING_KEY,
+        );
         init_account_transactions.push(tx);
     }
 
     let json = serde_json::to_string_pretty(&init_account_transactions).unwrap();
     fs::write("mock_data/init_account_txns.json", json).unwrap();  
 
-    let (_, header, _, _) = execute_batch::<Prover, Proof, ZKVM>(
+    let (_, header, _, _, _) = execute_batch::<Proof>(
         &init_account_transactions,
         state_machine,
         &avail_headers[1],
@@ -128,6 +136,7 @@ async fn generate_submit_proof_transactions(
             rollup_start_height: 606460,
             prover_mode: prover_mode.clone(),
             avail_url: String::from("wss://turing-rpc.avail.so:443/ws"),
+            signing_key: BENCH_SIGNING_KEY,
         };
 
         // Retrieve or initialize the adapter state data from the database
@@ -159,6 +168,8 @@ async fn generate_submit_proof_transactions(
             app_id: app_account_id.clone(),
             img_id: StatementDigest(ADAPTER_ID),
             rollup_hash: Some(H256::zero()),
+            message_root: None,
+            storage_hash: None,
         };
 
         let mut env_builder = ExecutorEnv::builder();
@@ -174,9 +185,8 @@ async fn generate_submit_proof_transactions(
 
         let recursive_proof = Proof(prove_info.receipt);
 
-        let tx = Transaction {
-            signature: TxSignature([0u8; 64]),
-            params: TxParams::SubmitProof(SubmitProof {
+        let tx = Transaction::new_signed(
+            TxParams::SubmitProof(SubmitProof {
                 app_id: app_account_id.clone(),
                 nexus_hash: header.hash(),
                 state_root: public_inputs.state_root.clone(),
@@ -189,8 +199,12 @@ async fn generate_submit_proof_transactions(
                 },
                 height: public_inputs.height,
                 data: None,
+                message_root: None,
+                storage_hash: None,
             }),
-        };
+            account_with_proof.account.nonce,
+            &BENCH_SIGNING_KEY,
+        );
 
         submit_proof_transactions.push(tx.clone());
     }
@@ -286,7 +300,7 @@ async fn main() -> Result<(), Error> {
         }
     };
 
-    let (_, header, _, _) = execute_batch::<Prover, Proof, ZKVM>(
+    let (_, header, _, _, _) = execute_batch::<Proof>(
         &mock_txs,
         &mut state_machine,
         &avail_headers[0],
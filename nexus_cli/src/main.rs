@@ -190,23 +190,36 @@ fn run_nexus(nexus_dir: &Path, dev: bool, zkvm: Option<ZKVMOptions>) {
         None => ZKVMOptions::SP1,
     };
 
+    // Only risc0 is compiled into the nexus binary by default, sp1 is built as a feature.
     match zkvm {
         ZKVMOptions::Risc0 => {
-            command.arg("run").current_dir(nexus_dir);
+            command.arg("run").arg("--release");
         }
+        //Mock sp1 proofs are only accepted by dev builds.
         ZKVMOptions::SP1 => {
             command
                 .arg("run")
                 .arg("--no-default-features")
-                .arg("--features=sp1")
-                .arg("--release")
-                .current_dir(nexus_dir);
+                .arg(if dev {
+                    "--features=sp1,dev"
+                } else {
+                    "--features=sp1"
+                })
+                .arg("--release");
         }
     }
 
+    command
+        .arg("--")
+        .arg(match zkvm {
+            ZKVMOptions::Risc0 => "--zkvm=risc0",
+            ZKVMOptions::SP1 => "--zkvm=sp1",
+        })
+        .current_dir(nexus_dir);
+
     if dev {
         command.env("RISC0_DEV_MODE", "true");
-        command.arg("--").arg("--dev");
+        command.arg("--dev");
     }
 
     let status = command.status().expect("Failed to execute `cargo run`");
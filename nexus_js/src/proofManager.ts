@@ -48,12 +48,32 @@ class ProofManagerClient {
 
     const lastProofHeight = accountState.last_proof_height;
     const height = accountState.height;
+    const owner = "0x" + accountState.owner;
+    const nonce = accountState.nonce;
+    const pendingStatement = "0x" + accountState.pending_statement;
+    const pendingStatementHeight = accountState.pending_statement_height;
+    const messageRoot = "0x" + accountState.message_root;
+    const rollupHash = "0x" + accountState.rollup_hash;
+    const storageHash = "0x" + accountState.storage_hash;
+    const statementKind = accountState.statement_kind;
+    const pendingStatementKind = accountState.pending_statement_kind;
+    const generation = accountState.generation;
     const accountStateOnChain = {
       statementDigest,
       stateRoot,
       startNexusHash,
       lastProofHeight,
       height,
+      owner,
+      nonce,
+      pendingStatement,
+      pendingStatementHeight,
+      messageRoot,
+      rollupHash,
+      storageHash,
+      statementKind,
+      pendingStatementKind,
+      generation,
     };
 
     // Call the updateChainState function on the smart contract
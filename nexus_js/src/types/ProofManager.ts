@@ -27,6 +27,16 @@ export declare namespace NexusProofManager {
     startNexusHash: BytesLike;
     lastProofHeight: BigNumberish;
     height: BigNumberish;
+    owner: BytesLike;
+    nonce: BigNumberish;
+    pendingStatement: BytesLike;
+    pendingStatementHeight: BigNumberish;
+    messageRoot: BytesLike;
+    rollupHash: BytesLike;
+    storageHash: BytesLike;
+    statementKind: BigNumberish;
+    pendingStatementKind: BigNumberish;
+    generation: BigNumberish;
   };
 
   export type AccountStateStructOutput = [
@@ -34,13 +44,33 @@ export declare namespace NexusProofManager {
     stateRoot: string,
     startNexusHash: string,
     lastProofHeight: bigint,
-    height: bigint
+    height: bigint,
+    owner: string,
+    nonce: bigint,
+    pendingStatement: string,
+    pendingStatementHeight: bigint,
+    messageRoot: string,
+    rollupHash: string,
+    storageHash: string,
+    statementKind: bigint,
+    pendingStatementKind: bigint,
+    generation: bigint
   ] & {
     statementDigest: string;
     stateRoot: string;
     startNexusHash: string;
     lastProofHeight: bigint;
     height: bigint;
+    owner: string;
+    nonce: bigint;
+    pendingStatement: string;
+    pendingStatementHeight: bigint;
+    messageRoot: string;
+    rollupHash: string;
+    storageHash: string;
+    statementKind: bigint;
+    pendingStatementKind: bigint;
+    generation: bigint;
   };
 
   export type NexusBlockStruct = { stateRoot: BytesLike; blockHash: BytesLike };
@@ -159,6 +159,56 @@ const _abi = [
             type: "uint128",
             internalType: "uint128",
           },
+          {
+            name: "owner",
+            type: "bytes32",
+            internalType: "bytes32",
+          },
+          {
+            name: "nonce",
+            type: "uint64",
+            internalType: "uint64",
+          },
+          {
+            name: "pendingStatement",
+            type: "bytes32",
+            internalType: "bytes32",
+          },
+          {
+            name: "pendingStatementHeight",
+            type: "uint128",
+            internalType: "uint128",
+          },
+          {
+            name: "messageRoot",
+            type: "bytes32",
+            internalType: "bytes32",
+          },
+          {
+            name: "rollupHash",
+            type: "bytes32",
+            internalType: "bytes32",
+          },
+          {
+            name: "storageHash",
+            type: "bytes32",
+            internalType: "bytes32",
+          },
+          {
+            name: "statementKind",
+            type: "uint8",
+            internalType: "uint8",
+          },
+          {
+            name: "pendingStatementKind",
+            type: "uint8",
+            internalType: "uint8",
+          },
+          {
+            name: "generation",
+            type: "uint64",
+            internalType: "uint64",
+          },
         ],
       },
     ],
@@ -8,6 +8,16 @@ type AccountState = {
   start_nexus_hash: string;
   last_proof_height: number;
   height: number;
+  owner: string;
+  nonce: number;
+  pending_statement: string;
+  pending_statement_height: number;
+  message_root: string;
+  rollup_hash: string;
+  storage_hash: string;
+  statement_kind: number;
+  pending_statement_kind: number;
+  generation: number;
 };
 
 type AccountApiResponse = {